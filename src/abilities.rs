@@ -0,0 +1,276 @@
+use crate::*;
+use bevy_mod_picking::Selection;
+
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AbilityKind {
+    /// Halves the shooting timer while active
+    RapidFire,
+    /// Fires one bullet at every target in range at once
+    Volley,
+    /// Doubles beam damage while active
+    Overcharge,
+}
+
+impl AbilityKind {
+    pub fn name(&self) -> &'static str {
+        use AbilityKind::*;
+        match self {
+            RapidFire => "Rapid Fire",
+            Volley => "Volley",
+            Overcharge => "Overcharge",
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct Ability {
+    pub kind: AbilityKind,
+    pub cooldown: Timer,
+    /// Runs while the ability's effect lasts; instant abilities have a zero duration
+    pub active: Timer,
+}
+
+impl Ability {
+    /// Creates an ability that is ready to use straight away
+    pub fn new(kind: AbilityKind, cooldown_secs: f32, active_secs: f32) -> Self {
+        let mut cooldown = Timer::from_seconds(cooldown_secs, TimerMode::Once);
+        let mut active = Timer::from_seconds(active_secs, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        active.tick(active.duration());
+
+        Self {
+            kind,
+            cooldown,
+            active,
+        }
+    }
+
+    pub fn ready(&self) -> bool {
+        self.cooldown.finished()
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.active.finished()
+    }
+}
+
+pub struct ActivateAbilityEvent {
+    pub tower: Entity,
+}
+
+/// Towers whose ability was triggered since the last tick. Input arrives every frame but
+/// abilities take effect on the fixed tick, which may not run every frame
+#[derive(Resource, Default)]
+pub struct AbilityQueue(pub Vec<Entity>);
+
+#[derive(Component, Reflect)]
+pub struct AbilityButton;
+
+#[derive(Component, Reflect)]
+pub struct AbilityButtonText;
+
+pub struct AbilityPlugin;
+
+impl Plugin for AbilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Ability>()
+            .add_event::<ActivateAbilityEvent>()
+            .init_resource::<AbilityQueue>()
+            .add_system(
+                queue_abilities
+                    .in_set(FrameSet::Input)
+                    .in_set(OnUpdate(GameState::GamePlay)),
+            )
+            .add_system(
+                activate_abilities
+                    .in_set(SimulationSet::Spawn)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                tick_abilities
+                    .in_set(SimulationSet::Move)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
+
+/// The ability hotkey and the button showing the selected tower's cooldown
+pub struct AbilityViewPlugin;
+
+impl Plugin for AbilityViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (ability_hotkey, ability_button_clicked)
+                .before(queue_abilities)
+                .in_set(FrameSet::Input)
+                .in_set(OnUpdate(GameState::GamePlay)),
+        )
+        .add_system(
+            update_ability_button
+                .in_set(FrameSet::Ui)
+                .in_set(OnUpdate(GameState::GamePlay)),
+        );
+    }
+}
+
+fn ability_hotkey(
+    keyboard: Res<Input<KeyCode>>,
+    towers: Query<(Entity, &Selection), With<Ability>>,
+    mut activate: EventWriter<ActivateAbilityEvent>,
+) {
+    if keyboard.just_pressed(KeyCode::F) {
+        for (tower, selection) in &towers {
+            if selection.selected() {
+                activate.send(ActivateAbilityEvent { tower });
+            }
+        }
+    }
+}
+
+fn ability_button_clicked(
+    interactions: Query<&Interaction, (With<AbilityButton>, Changed<Interaction>)>,
+    towers: Query<(Entity, &Selection), With<Ability>>,
+    mut activate: EventWriter<ActivateAbilityEvent>,
+) {
+    for interaction in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            for (tower, selection) in &towers {
+                if selection.selected() {
+                    activate.send(ActivateAbilityEvent { tower });
+                }
+            }
+        }
+    }
+}
+
+fn queue_abilities(mut events: EventReader<ActivateAbilityEvent>, mut queue: ResMut<AbilityQueue>) {
+    queue.0.extend(events.iter().map(|event| event.tower));
+}
+
+fn activate_abilities(
+    mut commands: Commands,
+    mut queue: ResMut<AbilityQueue>,
+    mut towers: Query<(
+        &mut Ability,
+        &mut Tower,
+        &mut TowerStats,
+        &TowerType,
+        &Transform,
+    )>,
+    targeting: Targeting,
+    assets: Res<GameAssets>,
+    mut pool: ResMut<ProjectilePool>,
+    mut pool_stats: ResMut<ProjectilePoolStats>,
+) {
+    for tower_entity in queue.0.drain(..) {
+        let Ok((mut ability, mut tower, mut stats, tower_type, transform)) =
+            towers.get_mut(tower_entity)
+        else {
+            continue;
+        };
+        if !ability.ready() {
+            continue;
+        }
+
+        ability.cooldown.reset();
+        ability.active.reset();
+
+        match ability.kind {
+            AbilityKind::RapidFire => {
+                let duration = tower.shooting_timer.duration() / 2;
+                tower.shooting_timer.set_duration(duration);
+            }
+            AbilityKind::Volley => {
+                let bullet_spawn = transform.translation + tower.bullet_offset;
+
+                for (target, target_position) in targeting.candidates(&tower, bullet_spawn) {
+                    let Some(target_velocity) = targeting.velocity(target, target_position) else {
+                        continue;
+                    };
+
+                    stats.shots_fired += 1;
+                    fire_bullet(
+                        &mut commands,
+                        &assets,
+                        &mut pool,
+                        &mut pool_stats,
+                        tower_entity,
+                        &tower,
+                        *tower_type,
+                        transform.translation,
+                        target_position,
+                        target_velocity,
+                    );
+                }
+            }
+            AbilityKind::Overcharge => {
+                if let AttackMode::Beam {
+                    damage_per_second, ..
+                } = &mut tower.attack
+                {
+                    *damage_per_second *= 2.0;
+                }
+            }
+        }
+    }
+}
+
+/// Counts down cooldowns and undoes timed effects once they run out
+fn tick_abilities(mut towers: Query<(&mut Ability, &mut Tower)>, time: Res<FixedTime>) {
+    for (mut ability, mut tower) in &mut towers {
+        ability.cooldown.tick(time.period);
+        ability.active.tick(time.period);
+
+        if ability.active.just_finished() {
+            match ability.kind {
+                AbilityKind::RapidFire => {
+                    let duration = tower.shooting_timer.duration() * 2;
+                    tower.shooting_timer.set_duration(duration);
+                }
+                AbilityKind::Volley => {}
+                AbilityKind::Overcharge => {
+                    if let AttackMode::Beam {
+                        damage_per_second, ..
+                    } = &mut tower.attack
+                    {
+                        *damage_per_second /= 2.0;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn update_ability_button(
+    towers: Query<(&Selection, &Ability)>,
+    mut buttons: Query<&mut BackgroundColor, With<AbilityButton>>,
+    mut texts: Query<&mut Text, With<AbilityButtonText>>,
+) {
+    let Some((_, ability)) = towers.iter().find(|(selection, _)| selection.selected()) else {
+        return;
+    };
+
+    let label = if ability.is_active() {
+        format!("{} active", ability.kind.name())
+    } else if ability.ready() {
+        format!("{} [F]", ability.kind.name())
+    } else {
+        format!(
+            "{} {:.0}s",
+            ability.kind.name(),
+            ability.cooldown.remaining_secs().ceil()
+        )
+    };
+
+    for mut text in &mut texts {
+        text.sections[0].value = label.clone();
+    }
+
+    for mut tint in &mut buttons {
+        *tint = if ability.ready() {
+            Color::WHITE.into()
+        } else {
+            Color::DARK_GRAY.into()
+        };
+    }
+}
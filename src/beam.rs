@@ -0,0 +1,193 @@
+use crate::*;
+use bevy::utils::FloatOrd;
+
+/// Lock-on state for a tower using `AttackMode::Beam`
+#[derive(Component, Reflect, Default)]
+pub struct Beam {
+    pub target: Option<Entity>,
+    /// Seconds the current target has been held
+    pub locked_for: f32,
+    /// Fractional damage waiting to add up to a whole point of `Health`
+    pub damage_carry: f32,
+    pub visual: Option<Entity>,
+}
+
+#[derive(Component, Reflect)]
+pub struct BeamVisual;
+
+#[derive(Resource)]
+pub struct BeamAssets {
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+}
+
+pub struct BeamPlugin;
+
+impl Plugin for BeamPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Beam>().add_system(
+            beam_attack
+                .in_set(HitSet::Beam)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// Draws each `Beam` as a stretched mesh onto its target
+pub struct BeamViewPlugin;
+
+impl Plugin for BeamViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<BeamVisual>()
+            .add_system(load_beam_assets.in_schedule(OnEnter(GameState::GamePlay)))
+            .add_system(
+                update_beam_visuals
+                    .after(interpolate_transforms)
+                    .in_set(OnUpdate(GameState::GamePlay)),
+            );
+    }
+}
+
+fn load_beam_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(BeamAssets {
+        // Unit length along Y so it can be stretched with scale
+        mesh: meshes.add(shape::Box::new(0.04, 1.0, 0.04).into()),
+        material: materials.add(StandardMaterial {
+            base_color: Color::rgba(0.6, 1.0, 0.4, 0.8),
+            emissive: Color::rgb(0.4, 1.0, 0.2),
+            unlit: true,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+    });
+}
+
+fn beam_attack(
+    mut towers: Query<(Entity, &Tower, &mut Beam, &mut TowerStats, &Transform)>,
+    mut targets: Query<(Entity, &mut Health, &mut LastHitBy, &Transform), With<Target>>,
+    walkers: Query<&Target>,
+    grid: Res<TargetGrid>,
+    path: Res<TargetPath>,
+    rapier: Res<RapierContext>,
+    time: Res<FixedTime>,
+) {
+    for (tower_entity, tower, mut beam, mut stats, transform) in &mut towers {
+        let AttackMode::Beam {
+            damage_per_second,
+            ramp_per_second,
+            max_ramp,
+        } = tower.attack
+        else {
+            continue;
+        };
+
+        let origin = transform.translation + tower.bullet_offset;
+        let in_range = |target: Entity, position: Vec3| {
+            targets
+                .get(target)
+                .map_or(false, |(_, health, ..)| health.value > 0)
+                && walkers
+                    .get(target)
+                    .map_or(false, |walker| !walker.leaked(&path))
+                && Vec3::distance(position, origin) < tower.range
+                && line_of_sight(&rapier, origin, position)
+        };
+
+        // Hold the current lock while it stays valid, otherwise take the closest target
+        let held = beam.target.filter(|&target| {
+            targets.get(target).map_or(false, |(.., target_transform)| {
+                in_range(target, target_transform.translation)
+            })
+        });
+        let target = held.or_else(|| {
+            grid.within(origin, tower.range)
+                .filter(|&(target, position)| in_range(target, position))
+                .min_by_key(|(_, position)| FloatOrd(Vec3::distance(*position, origin)))
+                .map(|(entity, _)| entity)
+        });
+
+        if target != beam.target {
+            beam.target = target;
+            beam.locked_for = 0.0;
+            beam.damage_carry = 0.0;
+        }
+
+        let Some((_, mut health, mut last_hit, _)) =
+            target.and_then(|target| targets.get_mut(target).ok())
+        else {
+            continue;
+        };
+
+        let delta = time.period.as_secs_f32();
+        beam.locked_for += delta;
+        let ramp = (1.0 + ramp_per_second * beam.locked_for).min(max_ramp);
+        beam.damage_carry += damage_per_second * ramp * delta;
+
+        let damage = beam.damage_carry.floor() as i32;
+        if damage > 0 {
+            beam.damage_carry -= damage as f32;
+
+            let was_alive = health.value > 0;
+            let overkill = (damage - health.value.max(0)).max(0);
+            health.value -= damage;
+            last_hit.tower = Some(tower_entity);
+
+            stats.shots_fired += 1;
+            stats.hits += 1;
+            stats.damage_dealt += damage as u32;
+            stats.overkill += overkill as u32;
+            if was_alive && health.value <= 0 {
+                stats.kills += 1;
+            }
+        }
+    }
+}
+
+fn update_beam_visuals(
+    mut commands: Commands,
+    mut towers: Query<(Entity, &Tower, &mut Beam, &GlobalTransform)>,
+    targets: Query<&Transform, (With<Target>, Without<BeamVisual>)>,
+    mut visuals: Query<(&mut Transform, &mut Visibility), With<BeamVisual>>,
+    assets: Res<BeamAssets>,
+) {
+    for (tower_entity, tower, mut beam, transform) in &mut towers {
+        let Some(visual) = beam.visual else {
+            let visual = commands
+                .spawn(PbrBundle {
+                    mesh: assets.mesh.clone(),
+                    material: assets.material.clone(),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                })
+                .insert(BeamVisual)
+                .insert(Name::new("Beam"))
+                .id();
+            commands.entity(tower_entity).add_child(visual);
+            beam.visual = Some(visual);
+            continue;
+        };
+
+        let Ok((mut visual_transform, mut visibility)) = visuals.get_mut(visual) else {
+            continue;
+        };
+
+        match beam.target.and_then(|target| targets.get(target).ok()) {
+            Some(target_transform) => {
+                // Towers are never rotated or scaled, so tower-local space is a translation
+                let start = tower.bullet_offset;
+                let end = target_transform.translation - transform.translation();
+                let span = end - start;
+
+                *visual_transform = Transform::from_translation((start + end) / 2.0)
+                    .with_rotation(Quat::from_rotation_arc(Vec3::Y, span.normalize()))
+                    .with_scale(Vec3::new(1.0, span.length(), 1.0));
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
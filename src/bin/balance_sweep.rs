@@ -0,0 +1,175 @@
+//! Plays every tower type headless across a grid of stat multipliers and layouts, several
+//! seeded runs each, and writes one CSV row per configuration to stdout
+//!
+//! Usage: `balance_sweep [level] [seeds] > sweep.csv`
+
+use bevy::{prelude::*, utils::HashMap};
+use tower_game::*;
+
+/// Multipliers tried for each stat, the unchanged game is swept once as `baseline`
+const SCALES: [f32; 4] = [0.5, 0.75, 1.25, 1.5];
+
+const STATS: [&str; 4] = ["fire_rate", "range", "bullet_speed", "cost"];
+
+/// Ground positions towers are built on, in build order
+const LAYOUTS: [(&str, [Vec2; 3]); 3] = [
+    (
+        "bases",
+        [
+            Vec2::new(2.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(6.0, 0.0),
+        ],
+    ),
+    (
+        "bend",
+        [
+            Vec2::new(4.5, 3.5),
+            Vec2::new(7.5, 4.0),
+            Vec2::new(7.5, 7.5),
+        ],
+    ),
+    (
+        "entry",
+        [
+            Vec2::new(-8.0, 0.0),
+            Vec2::new(-6.0, 3.5),
+            Vec2::new(-3.0, 3.5),
+        ],
+    ),
+];
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let level = parse_arg(args.next(), 0, "level");
+    let seeds = parse_arg(args.next(), 5, "seeds");
+
+    let level_count = Levels::default().levels.len();
+    if level >= level_count {
+        eprintln!("There are only {level_count} levels");
+        std::process::exit(1);
+    }
+
+    // Enough to open with any tower at its highest swept cost, so every configuration
+    // gets built however expensive the tower is
+    let dearest = TowerTuning {
+        cost: SCALES.into_iter().fold(1.0, f32::max),
+        ..default()
+    };
+    let starting_money = TowerType::ALL
+        .into_iter()
+        .map(|tower_type| dearest.cost(tower_type))
+        .fold(STARTING_MONEY, u32::max);
+
+    let mut sweep = vec![("baseline", 1.0)];
+    for stat in STATS {
+        sweep.extend(SCALES.map(|scale| (stat, scale)));
+    }
+
+    println!(
+        "tower,layout,stat,scale,runs,victories,towers_built,dps_per_cost,leak_rate,\
+         overkill_rate,overkill,damage,kills"
+    );
+
+    for tower_type in TowerType::ALL {
+        let attack = tower_type.attack();
+
+        for (layout, positions) in LAYOUTS {
+            for &(stat, scale) in sweep.iter().filter(|(stat, _)| applies(stat, attack)) {
+                eprintln!("{tower_type:?} {layout} {stat} x{scale}");
+
+                let tuning = tuned(stat, scale);
+                let reports: Vec<_> = (0..seeds)
+                    .map(|seed| {
+                        run_simulation(SimulationConfig {
+                            level,
+                            build_order: positions.map(|position| (tower_type, position)).into(),
+                            starting_money,
+                            tuning: HashMap::from_iter([(tower_type, tuning)]),
+                            seed: Some(seed),
+                            ..default()
+                        })
+                    })
+                    .collect();
+
+                assert!(
+                    reports.iter().any(|report| !report.towers.is_empty()),
+                    "No {tower_type:?} tower was built in any run of {layout} {stat} x{scale}"
+                );
+
+                println!(
+                    "{tower_type:?},{layout},{stat},{scale},{}",
+                    summarise(&reports)
+                );
+            }
+        }
+    }
+}
+
+fn parse_arg<T: std::str::FromStr>(arg: Option<String>, default: T, name: &str) -> T {
+    match arg {
+        None => default,
+        Some(arg) => arg.parse().unwrap_or_else(|_| {
+            eprintln!("Expected a number of {name}, got `{arg}`");
+            std::process::exit(1);
+        }),
+    }
+}
+
+/// Whether changing `stat` makes any difference to a tower attacking with `attack`.
+/// Beams fire no bullets, so their bullet speed would only repeat the baseline
+fn applies(stat: &str, attack: AttackMode) -> bool {
+    !(stat == "bullet_speed" && matches!(attack, AttackMode::Beam { .. }))
+}
+
+fn tuned(stat: &str, scale: f32) -> TowerTuning {
+    let mut tuning = TowerTuning::default();
+    match stat {
+        "fire_rate" => tuning.fire_rate = scale,
+        "range" => tuning.range = scale,
+        "bullet_speed" => tuning.bullet_speed = scale,
+        "cost" => tuning.cost = scale,
+        _ => {}
+    }
+    tuning
+}
+
+/// The CSV columns after the configuration, averaged over the seeded runs
+fn summarise(reports: &[SimulationReport]) -> String {
+    let runs = reports.len();
+    let victories = reports
+        .iter()
+        .filter(|report| report.outcome == Some(Outcome::Victory))
+        .count();
+
+    let towers = || reports.iter().flat_map(|report| &report.towers);
+    let towers_built = towers().count() as f32 / runs.max(1) as f32;
+    let tower_count = towers().count().max(1) as f32;
+    let dps_per_cost = reports
+        .iter()
+        .flat_map(|report| {
+            report
+                .towers
+                .iter()
+                .map(|tower| tower.dps_per_cost(report.seconds))
+        })
+        .sum::<f32>()
+        / tower_count;
+
+    let leak_rate =
+        reports.iter().map(|report| report.leak_rate()).sum::<f32>() / runs.max(1) as f32;
+
+    let damage: u32 = towers().map(|tower| tower.stats.damage_dealt).sum();
+    let overkill: u32 = towers().map(|tower| tower.stats.overkill).sum();
+    let kills: u32 = towers().map(|tower| tower.stats.kills).sum();
+    let overkill_rate = if damage == 0 {
+        0.0
+    } else {
+        overkill as f32 / damage as f32
+    };
+
+    format!(
+        "{runs},{victories},{towers_built:.2},{dps_per_cost:.5},{leak_rate:.4},\
+         {overkill_rate:.4},{overkill},{damage},{kills}"
+    )
+}
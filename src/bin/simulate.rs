@@ -0,0 +1,100 @@
+//! Plays a level headless with a scripted tower layout as fast as possible and prints
+//! how it went
+//!
+//! Usage: `simulate [level] [tower@x,z ...]`, e.g. `simulate 1 potato@2,0 cabbage@-3,1`
+
+use bevy::prelude::*;
+use tower_game::*;
+
+fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    let mut config = SimulationConfig::default();
+
+    if let Some(level) = args.peek().and_then(|arg| arg.parse().ok()) {
+        config.level = level;
+        args.next();
+    }
+
+    let build_order: Vec<_> = args.map(|arg| parse_tower(&arg)).collect();
+    if !build_order.is_empty() {
+        config.build_order = build_order;
+    }
+
+    let level_count = Levels::default().levels.len();
+    if config.level >= level_count {
+        eprintln!("There are only {level_count} levels");
+        std::process::exit(1);
+    }
+
+    let report = run_simulation(config);
+    print_report(&report);
+}
+
+fn parse_tower(arg: &str) -> (TowerType, Vec2) {
+    let parsed = arg.split_once('@').and_then(|(name, position)| {
+        let tower_type = match name.to_lowercase().as_str() {
+            "tomato" => TowerType::Tomato,
+            "potato" => TowerType::Potato,
+            "cabbage" => TowerType::Cabbage,
+            "mortar" => TowerType::Mortar,
+            "laser" => TowerType::Laser,
+            _ => return None,
+        };
+        let (x, z) = position.split_once(',')?;
+        Some((tower_type, Vec2::new(x.parse().ok()?, z.parse().ok()?)))
+    });
+
+    parsed.unwrap_or_else(|| {
+        eprintln!("Expected a tower like `potato@2,0`, got `{arg}`");
+        std::process::exit(1);
+    })
+}
+
+fn print_report(report: &SimulationReport) {
+    println!(
+        "Outcome: {:?} after {:.1}s ({} ticks)",
+        report.outcome.unwrap_or(Outcome::TimedOut),
+        report.seconds,
+        report.ticks
+    );
+    println!(
+        "Leaks: {}/{} ({:.0}%)",
+        report.leaks,
+        report.targets,
+        report.leak_rate() * 100.0
+    );
+
+    println!("\nWaves cleared:");
+    let mut previous = 0.0;
+    for (wave, cleared_at) in report.wave_clear_times.iter().enumerate() {
+        println!(
+            "  {:>2}: {:>7.1}s (+{:.1}s)",
+            wave + 1,
+            cleared_at,
+            cleared_at - previous
+        );
+        previous = *cleared_at;
+    }
+
+    println!("\nTowers:");
+    for tower in &report.towers {
+        println!(
+            "  {:?} at ({}, {}) built {:.1}s: {} kills, {} damage ({} overkill), \
+             {:.0}% accuracy, {:.4} DPS per cost",
+            tower.tower_type,
+            tower.position.x,
+            tower.position.y,
+            tower.built_at,
+            tower.stats.kills,
+            tower.stats.damage_dealt,
+            tower.stats.overkill,
+            tower.stats.accuracy() * 100.0,
+            tower.dps_per_cost(report.seconds)
+        );
+    }
+
+    println!("\nMoney:");
+    for (seconds, money) in &report.money {
+        println!("  {seconds:>7.1}s {money:>6}");
+    }
+}
@@ -0,0 +1,191 @@
+use crate::*;
+use bevy::ecs::system::SystemParam;
+use bevy_mod_picking::Selection;
+
+/// Translucent stand-in for the tower whose build button is hovered
+#[derive(Component)]
+pub struct BuildPreview {
+    pub tower_type: TowerType,
+    pub base: Entity,
+    pub affordable: bool,
+}
+
+/// What a preview is built from
+#[derive(SystemParam)]
+pub struct PreviewAssets<'w> {
+    game: Res<'w, GameAssets>,
+    server: Res<'w, AssetServer>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+}
+
+#[derive(Component, Reflect)]
+pub struct BuildPreviewText;
+
+/// Marks preview meshes whose material has already been swapped for a see-through one
+#[derive(Component)]
+pub struct GhostTinted;
+
+pub struct BuildPreviewPlugin;
+
+impl Plugin for BuildPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<BuildPreviewText>().add_systems(
+            (
+                update_build_preview.after(create_ui_on_selection),
+                tint_build_preview.after(update_build_preview),
+            )
+                .in_set(OnUpdate(GameState::GamePlay)),
+        );
+    }
+}
+
+fn update_build_preview(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &TowerType, &TowerButtonState)>,
+    bases: Query<(Entity, &Selection, &GlobalTransform), With<TowerBase>>,
+    previews: Query<(Entity, &BuildPreview)>,
+    texts: Query<Entity, With<BuildPreviewText>>,
+    mut assets: PreviewAssets,
+) {
+    let hovered = buttons
+        .iter()
+        .find(|(interaction, ..)| !matches!(interaction, Interaction::None))
+        .map(|(_, tower_type, state)| (*tower_type, state.affordable));
+    let base = bases
+        .iter()
+        .find(|(_, selection, _)| selection.selected())
+        .map(|(entity, _, transform)| (entity, transform.translation()));
+
+    let wanted = hovered.zip(base);
+
+    let up_to_date = previews.iter().any(|(_, preview)| {
+        wanted.map_or(false, |((tower_type, affordable), (base, _))| {
+            preview.tower_type == tower_type
+                && preview.affordable == affordable
+                && preview.base == base
+        })
+    });
+    if up_to_date || (wanted.is_none() && previews.is_empty()) {
+        return;
+    }
+
+    for (entity, _) in &previews {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in &texts {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(((tower_type, affordable), (base, position))) = wanted else {
+        return;
+    };
+    let (tower_scene, tower) = tower_type.get_tower(&assets.game);
+
+    let tint = if affordable {
+        Color::rgba(0.3, 0.9, 0.3, 0.5)
+    } else {
+        Color::rgba(0.9, 0.2, 0.2, 0.5)
+    };
+
+    commands
+        .spawn(SpatialBundle::from_transform(Transform::from_translation(
+            position,
+        )))
+        .insert(BuildPreview {
+            tower_type,
+            base,
+            affordable,
+        })
+        .insert(GamePlayEntity)
+        .insert(Name::new("Build Preview"))
+        .with_children(|commands| {
+            commands.spawn(SceneBundle {
+                scene: tower_scene,
+                transform: Transform::from_xyz(0.0, -0.8, 0.0),
+                ..default()
+            });
+
+            commands
+                .spawn(PbrBundle {
+                    mesh: assets.meshes.add(
+                        shape::Torus {
+                            radius: tower.range,
+                            ring_radius: 0.03,
+                            ..default()
+                        }
+                        .into(),
+                    ),
+                    material: assets.materials.add(StandardMaterial {
+                        base_color: tint,
+                        unlit: true,
+                        alpha_mode: AlphaMode::Blend,
+                        ..default()
+                    }),
+                    transform: Transform::from_xyz(0.0, -0.78, 0.0),
+                    ..default()
+                })
+                .insert(GhostTinted)
+                .insert(Name::new("Range Ring"));
+        });
+
+    commands
+        .spawn(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: Val::Percent(20.0),
+                    left: Val::Percent(45.0),
+                    ..default()
+                },
+                ..default()
+            },
+            text: Text::from_section(
+                format!("{tower_type:?} Tower: {}", tower_type.cost()),
+                TextStyle {
+                    font: assets.server.load("FiraSans-Bold.ttf"),
+                    font_size: 32.0,
+                    color: if affordable { Color::BLACK } else { Color::RED },
+                },
+            ),
+            ..default()
+        })
+        .insert(BuildPreviewText)
+        .insert(GamePlayEntity);
+}
+
+/// Swaps the materials of freshly spawned preview scenes for translucent copies
+fn tint_build_preview(
+    mut commands: Commands,
+    previews: Query<(Entity, &BuildPreview)>,
+    children: Query<&Children>,
+    mut handles: Query<&mut Handle<StandardMaterial>, Without<GhostTinted>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (root, preview) in &previews {
+        let mut stack = vec![root];
+
+        while let Some(entity) = stack.pop() {
+            if let Ok(entity_children) = children.get(entity) {
+                stack.extend(entity_children.iter().copied());
+            }
+
+            let Ok(mut handle) = handles.get_mut(entity) else {
+                continue;
+            };
+            let Some(mut material) = materials.get(&handle).cloned() else {
+                continue;
+            };
+
+            material.base_color = if preview.affordable {
+                material.base_color.with_a(0.4)
+            } else {
+                Color::rgba(1.0, 0.2, 0.2, 0.4)
+            };
+            material.alpha_mode = AlphaMode::Blend;
+
+            *handle = materials.add(material);
+            commands.entity(entity).insert(GhostTinted);
+        }
+    }
+}
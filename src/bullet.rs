@@ -1,14 +1,77 @@
 use crate::*;
+use bevy::{
+    ecs::event::ManualEventReader,
+    math::Vec3Swizzles,
+    utils::{FloatOrd, HashSet},
+};
+use bevy_rapier3d::prelude::*;
 
+/// How close a bullet must get to a target's origin to hit it
+pub const HIT_RADIUS: f32 = 0.3;
+
+/// Downward acceleration applied to `Ballistic` bullets
+pub const GRAVITY: f32 = 9.81;
+
+/// How far past its tower's range a bullet may fly before it is removed
+pub const RANGE_MARGIN: f32 = 1.0;
+
+/// Removes a bullet once it is `max_distance` away from where it was fired
 #[derive(Component, Reflect)]
 pub struct Lifetime {
-    pub timer: Timer,
+    pub origin: Vec3,
+    pub max_distance: f32,
 }
 
 #[derive(Component, Reflect)]
 pub struct Bullet {
     pub direction: Vec3,
     pub speed: f32,
+    pub damage: i32,
+    pub tower_type: TowerType,
+    /// The tower that fired this bullet. It may have been removed while the bullet
+    /// was in flight
+    pub owner: Entity,
+}
+
+/// Where a bullet was before its latest move, so hits are tested along the whole
+/// segment it travelled rather than only at its current position
+#[derive(Component, Reflect)]
+pub struct PreviousPosition(pub Vec3);
+
+/// A bullet that arcs under gravity and explodes when it reaches the ground
+#[derive(Component, Reflect)]
+pub struct Ballistic {
+    pub velocity: Vec3,
+    pub blast_radius: f32,
+}
+
+/// How bullets decide they have hit a target
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HitDetection {
+    /// Distance check between bullet and target origins against `HIT_RADIUS`
+    #[default]
+    Distance,
+    /// Intersections between the bullet and target sensor colliders. Rapier steps once
+    /// per frame rather than per tick, so runs using this are not reproducible
+    Physics,
+}
+
+/// Sent whenever a bullet damages a target
+pub struct BulletHitEvent {
+    pub bullet: Entity,
+    pub target: Entity,
+    /// The tower that fired the bullet
+    pub tower: Entity,
+    pub tower_type: TowerType,
+    pub damage: i32,
+    /// Part of `damage` beyond the health the target had left
+    pub overkill: i32,
+    pub impact: Vec3,
+    /// Whether this hit took the target's health to zero
+    pub killed: bool,
+    /// Set for every target after the first caught by the same blast, so a shell
+    /// counts as one hit however much it splashes
+    pub splash: bool,
 }
 
 pub struct BulletPlugin;
@@ -17,47 +80,329 @@ impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Lifetime>()
             .register_type::<Bullet>()
+            .register_type::<Ballistic>()
+            .register_type::<PreviousPosition>()
+            .init_resource::<HitDetection>()
+            .init_resource::<ProjectilePool>()
+            .init_resource::<ProjectilePoolStats>()
+            .register_type::<ProjectilePoolStats>()
+            .add_event::<ReleaseBulletEvent>()
+            .add_event::<BulletHitEvent>()
             // .add_system(move_bullets)
             // .add_system(bullet_collision)
             // .add_system(bullet_despawn);
             .add_systems(
-                (bullet_collision, move_bullets, bullet_despawn)
-                    .in_set(OnUpdate(GameState::GamePlay)),
+                (move_bullets, move_ballistic)
+                    .in_set(SimulationSet::Move)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_systems(
+                (
+                    bullet_collision.run_if(resource_equals(HitDetection::Distance)),
+                    bullet_sensor_hits.run_if(resource_equals(HitDetection::Physics)),
+                )
+                    .in_set(HitSet::Impact)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                bullet_despawn
+                    .in_set(HitSet::Expire)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                ballistic_detonation
+                    .in_set(HitSet::Blast)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                recycle_bullets
+                    .in_set(SimulationSet::Resolve)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             );
     }
 }
 
+/// Shells are left to `ballistic_detonation`, as a lobbed shell leading a target near
+/// the edge of its range can land further than `max_distance` from where it was fired
 fn bullet_despawn(
-    mut commands: Commands,
-    mut bullets: Query<(Entity, &mut Lifetime)>,
-    time: Res<Time>,
+    bullets: Query<(Entity, &Lifetime, &Transform), Without<Ballistic>>,
+    mut release: EventWriter<ReleaseBulletEvent>,
 ) {
-    for (entity, mut bullet) in &mut bullets {
-        bullet.timer.tick(time.delta());
-        if bullet.timer.just_finished() {
-            commands.entity(entity).despawn_recursive();
+    for (entity, lifetime, transform) in &bullets {
+        if transform.translation.distance(lifetime.origin) >= lifetime.max_distance {
+            release.send(ReleaseBulletEvent { bullet: entity });
         }
     }
 }
 
 fn bullet_collision(
-    mut commands: Commands,
-    bullets: Query<(Entity, &GlobalTransform), With<Bullet>>,
-    mut targets: Query<(&mut Health, &Transform), With<Target>>,
+    mut release: EventWriter<ReleaseBulletEvent>,
+    bullets: Query<(Entity, &Bullet, &Transform, &PreviousPosition), Without<Ballistic>>,
+    mut targets: Query<(&mut Health, &mut LastHitBy), With<Target>>,
+    mut hits: EventWriter<BulletHitEvent>,
+    grid: Res<TargetGrid>,
+) {
+    for (entity, bullet, bullet_transform, previous) in &bullets {
+        let start = previous.0;
+        let end = bullet_transform.translation;
+        let reach = start.distance(end) / 2.0 + HIT_RADIUS;
+
+        // The first target along the travelled segment takes the hit
+        let hit = grid
+            .within((start + end) / 2.0, reach)
+            .filter(|(target, _)| targets.contains(*target))
+            .filter_map(|(target, position)| {
+                segment_sphere_hit(start, end, position, HIT_RADIUS).map(|t| (target, t))
+            })
+            .min_by_key(|(_, t)| FloatOrd(*t));
+
+        let Some((target, t)) = hit else {
+            continue;
+        };
+        let Ok((mut target_health, mut last_hit)) = targets.get_mut(target) else {
+            continue;
+        };
+
+        release.send(ReleaseBulletEvent { bullet: entity });
+        hits.send(damage_target(
+            entity,
+            bullet,
+            target,
+            start.lerp(end, t),
+            &mut target_health,
+            &mut last_hit,
+        ));
+    }
+}
+
+/// Fraction along the segment from `start` to `end` at which it first touches the
+/// sphere around `center`, or `None` if it misses
+pub fn segment_sphere_hit(start: Vec3, end: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let offset = start - center;
+    let c = offset.length_squared() - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+
+    let travel = end - start;
+    let a = travel.length_squared();
+    let b = offset.dot(travel);
+    let discriminant = b * b - a * c;
+    if a <= f32::EPSILON || b > 0.0 || discriminant < 0.0 {
+        return None;
+    }
+
+    let t = (-b - discriminant.sqrt()) / a;
+    (t <= 1.0).then_some(t)
+}
+
+fn bullet_sensor_hits(
+    mut release: EventWriter<ReleaseBulletEvent>,
+    mut collisions: EventReader<CollisionEvent>,
+    bullets: Query<(&Bullet, &Transform), Without<Ballistic>>,
+    mut targets: Query<(&Target, &mut Health, &mut LastHitBy)>,
+    mut hits: EventWriter<BulletHitEvent>,
+    path: Res<TargetPath>,
 ) {
-    for (bullet, bullet_transform) in &bullets {
-        for (mut target_health, target_transform) in &mut targets {
-            if Vec3::distance(bullet_transform.translation(), target_transform.translation) < 0.3 {
-                commands.entity(bullet).despawn_recursive();
-                target_health.value -= 1;
-                break;
-            }
+    // A bullet can touch several targets in one step but only hits the first
+    let mut spent = HashSet::new();
+
+    for collision in collisions.iter() {
+        let CollisionEvent::Started(a, b, _) = *collision else {
+            continue;
+        };
+        let (entity, target) = if bullets.contains(a) { (a, b) } else { (b, a) };
+
+        let Ok((bullet, bullet_transform)) = bullets.get(entity) else {
+            continue;
+        };
+        let Ok((target_component, mut target_health, mut last_hit)) = targets.get_mut(target)
+        else {
+            continue;
+        };
+        if target_component.leaked(&path) || !spent.insert(entity) {
+            continue;
         }
+
+        release.send(ReleaseBulletEvent { bullet: entity });
+        hits.send(damage_target(
+            entity,
+            bullet,
+            target,
+            bullet_transform.translation,
+            &mut target_health,
+            &mut last_hit,
+        ));
     }
 }
 
-fn move_bullets(mut bullets: Query<(&Bullet, &mut Transform)>, time: Res<Time>) {
-    for (bullet, mut transform) in &mut bullets {
-        transform.translation += bullet.direction.normalize() * bullet.speed * time.delta_seconds();
+/// Applies one bullet's damage to a target and returns the hit to announce
+fn damage_target(
+    bullet_entity: Entity,
+    bullet: &Bullet,
+    target: Entity,
+    impact: Vec3,
+    health: &mut Health,
+    last_hit: &mut LastHitBy,
+) -> BulletHitEvent {
+    let was_alive = health.value > 0;
+    let overkill = (bullet.damage - health.value.max(0)).max(0);
+    health.value -= bullet.damage;
+    last_hit.tower = Some(bullet.owner);
+
+    BulletHitEvent {
+        bullet: bullet_entity,
+        target,
+        tower: bullet.owner,
+        tower_type: bullet.tower_type,
+        damage: bullet.damage,
+        overkill,
+        impact,
+        killed: was_alive && health.value <= 0,
+        splash: false,
+    }
+}
+
+fn move_bullets(
+    mut bullets: Query<(&Bullet, &mut Transform, &mut PreviousPosition), Without<Ballistic>>,
+    time: Res<FixedTime>,
+) {
+    let delta = time.period.as_secs_f32();
+    for (bullet, mut transform, mut previous) in &mut bullets {
+        previous.0 = transform.translation;
+        transform.translation += bullet.direction.normalize() * bullet.speed * delta;
+    }
+}
+
+/// Launch velocity that carries a bullet from `from` onto a target currently at
+/// `target` moving with `target_velocity`, travelling at a constant `horizontal_speed`
+pub fn ballistic_launch_velocity(
+    from: Vec3,
+    target: Vec3,
+    target_velocity: Vec3,
+    horizontal_speed: f32,
+) -> Vec3 {
+    // Refine the lead a few times as the flight time depends on where we aim
+    let mut aim = target;
+    for _ in 0..3 {
+        let flight_time = (aim - from).xz().length() / horizontal_speed;
+        aim = target + target_velocity * flight_time;
+    }
+
+    let offset = aim - from;
+    let flight_time = (offset.xz().length() / horizontal_speed).max(0.1);
+    let horizontal = offset.xz() / flight_time;
+    let vertical = (offset.y + 0.5 * GRAVITY * flight_time * flight_time) / flight_time;
+
+    Vec3::new(horizontal.x, vertical, horizontal.y)
+}
+
+fn move_ballistic(
+    mut bullets: Query<(&mut Ballistic, &mut Transform, &mut PreviousPosition)>,
+    time: Res<FixedTime>,
+) {
+    let delta = time.period.as_secs_f32();
+    for (mut ballistic, mut transform, mut previous) in &mut bullets {
+        previous.0 = transform.translation;
+        ballistic.velocity.y -= GRAVITY * delta;
+        transform.translation += ballistic.velocity * delta;
+    }
+}
+
+/// Shells only damage through their blast, so they never go through the direct hit
+/// systems. Any shell already released this tick is skipped
+fn ballistic_detonation(
+    mut release: ResMut<Events<ReleaseBulletEvent>>,
+    mut released: Local<ManualEventReader<ReleaseBulletEvent>>,
+    bullets: Query<(Entity, &Bullet, &Ballistic, &Transform)>,
+    mut targets: Query<(&mut Health, &mut LastHitBy), With<Target>>,
+    mut hits: EventWriter<BulletHitEvent>,
+    grid: Res<TargetGrid>,
+) {
+    let spent: HashSet<Entity> = released.iter(&release).map(|event| event.bullet).collect();
+
+    for (entity, bullet, ballistic, bullet_transform) in &bullets {
+        let impact = bullet_transform.translation;
+        if impact.y > 0.0 || spent.contains(&entity) {
+            continue;
+        }
+
+        release.send(ReleaseBulletEvent { bullet: entity });
+
+        // Targets walk a little above the ground, so look a bit further than the blast
+        // and then measure it along the ground
+        let caught = grid
+            .within(impact, ballistic.blast_radius + HIT_RADIUS)
+            .filter(|(_, position)| position.xz().distance(impact.xz()) <= ballistic.blast_radius);
+
+        let mut splash = false;
+        for (target, _) in caught {
+            let Ok((mut target_health, mut last_hit)) = targets.get_mut(target) else {
+                continue;
+            };
+
+            let hit = damage_target(
+                entity,
+                bullet,
+                target,
+                impact,
+                &mut target_health,
+                &mut last_hit,
+            );
+            hits.send(BulletHitEvent { splash, ..hit });
+            splash = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_starting_inside_hits_immediately() {
+        let hit = segment_sphere_hit(Vec3::new(0.1, 0.0, 0.0), Vec3::X, Vec3::ZERO, 0.3);
+        assert_eq!(hit, Some(0.0));
+    }
+
+    #[test]
+    fn segment_passing_just_outside_misses() {
+        let start = Vec3::new(-1.0, 0.31, 0.0);
+        let end = Vec3::new(1.0, 0.31, 0.0);
+        assert_eq!(segment_sphere_hit(start, end, Vec3::ZERO, 0.3), None);
+    }
+
+    #[test]
+    fn segment_moving_away_misses() {
+        let start = Vec3::new(0.5, 0.0, 0.0);
+        let end = Vec3::new(1.5, 0.0, 0.0);
+        assert_eq!(segment_sphere_hit(start, end, Vec3::ZERO, 0.3), None);
+    }
+
+    #[test]
+    fn segment_reaching_sphere_at_its_end_hits() {
+        let start = Vec3::new(-1.3, 0.0, 0.0);
+
+        let t = segment_sphere_hit(start, Vec3::new(-0.29, 0.0, 0.0), Vec3::ZERO, 0.3)
+            .expect("segment ends inside the sphere");
+        assert!((t - 1.0 / 1.01).abs() < 1e-4, "hit at {t}");
+
+        let short = segment_sphere_hit(start, Vec3::new(-0.31, 0.0, 0.0), Vec3::ZERO, 0.3);
+        assert_eq!(short, None);
+    }
+
+    #[test]
+    fn launch_velocity_lands_on_stationary_target() {
+        let from = Vec3::new(0.0, 0.6, 0.0);
+        let target = Vec3::new(3.0, 0.2, 1.0);
+        let speed = 3.5;
+
+        let velocity = ballistic_launch_velocity(from, target, Vec3::ZERO, speed);
+        assert!((velocity.xz().length() - speed).abs() < 1e-4);
+
+        let flight_time = (target - from).xz().length() / speed;
+        let landing =
+            from + velocity * flight_time - Vec3::Y * 0.5 * GRAVITY * flight_time * flight_time;
+        assert!(landing.distance(target) < 1e-4, "landed at {landing}");
     }
 }
@@ -0,0 +1,32 @@
+use crate::*;
+
+/// Marks an entity as part of a `GamePlay` session. Every marked entity, along with its
+/// children, is despawned when `GamePlay` exits, so a new session starts from scratch
+#[derive(Component, Reflect, Default)]
+pub struct GamePlayEntity;
+
+pub struct CleanupPlugin;
+
+impl Plugin for CleanupPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GamePlayEntity>().add_systems(
+            (despawn_with::<GamePlayEntity>, reset_session_resources)
+                .in_schedule(OnExit(GameState::GamePlay)),
+        );
+    }
+}
+
+/// Despawns every entity with the marker `T`, for use on a state's exit
+pub fn despawn_with<T: Component>(mut commands: Commands, entities: Query<Entity, With<T>>) {
+    for entity in &entities {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Resources that refer to entities of the session being torn down
+fn reset_session_resources(mut commands: Commands) {
+    commands.insert_resource(ProjectilePool::default());
+    commands.insert_resource(ProjectilePoolStats::default());
+    commands.insert_resource(Placement::default());
+    commands.insert_resource(AbilityQueue::default());
+}
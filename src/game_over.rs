@@ -0,0 +1,222 @@
+use crate::*;
+
+/// Rows of the tower table on the results screens, the rest are summed up in one line
+const TOWER_TABLE_ROWS: usize = 6;
+
+/// How the current run is going, shown on the game over and victory screens
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct GameResults {
+    pub waves_survived: usize,
+    pub kills: u32,
+    pub money_earned: u32,
+    /// Star rating for a won level
+    pub stars: u32,
+    /// Name and stats of every tower built, most damage first
+    #[reflect(ignore)]
+    pub towers: Vec<(String, TowerStats)>,
+}
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GameResults>()
+            .init_resource::<GameResults>()
+            .add_system(reset_results.in_schedule(OnEnter(GameState::GamePlay)));
+    }
+}
+
+/// The game over screen with the run's results and Restart and Main Menu buttons
+pub struct GameOverViewPlugin;
+
+impl Plugin for GameOverViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(spawn_game_over_ui.in_schedule(OnEnter(GameState::GameOver)))
+            .add_system(despawn_with::<GameOverUIRoot>.in_schedule(OnExit(GameState::GameOver)))
+            .add_systems(
+                (restart_button_clicked, menu_button_clicked).in_set(OnUpdate(GameState::GameOver)),
+            );
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct GameOverUIRoot;
+
+#[derive(Component, Reflect)]
+pub struct RestartButton;
+
+#[derive(Component, Reflect)]
+pub struct MenuButton;
+
+fn reset_results(mut results: ResMut<GameResults>) {
+    *results = GameResults::default();
+}
+
+fn spawn_game_over_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut results: ResMut<GameResults>,
+    waves: Res<Waves>,
+) {
+    results.waves_survived = waves.cleared;
+
+    let restart_button = spawn_button(&mut commands, &asset_server, "Restart", Color::RED);
+    commands.entity(restart_button).insert(RestartButton);
+    let menu_button = spawn_button(&mut commands, &asset_server, "Main Menu", Color::BLUE);
+    commands.entity(menu_button).insert(MenuButton);
+
+    let font = asset_server.load("FiraSans-Bold.ttf");
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .insert(GameOverUIRoot)
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(2.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    "Game Over",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 96.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                ..default()
+            });
+
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(1.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    format!(
+                        "Waves survived: {}\nKills: {}\nMoney earned: {}",
+                        results.waves_survived, results.kills, results.money_earned
+                    ),
+                    TextStyle {
+                        font,
+                        font_size: 36.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                ..default()
+            });
+        })
+        .with_children(|commands| spawn_tower_table(commands, &asset_server, &results.towers))
+        .add_child(restart_button)
+        .add_child(menu_button);
+}
+
+/// Damage, kills, shots and hits of the towers that did the most damage
+pub fn spawn_tower_table(
+    commands: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    towers: &[(String, TowerStats)],
+) {
+    if towers.is_empty() {
+        return;
+    }
+
+    let style = TextStyle {
+        font: asset_server.load("FiraSans-Bold.ttf"),
+        font_size: 24.0,
+        color: Color::WHITE,
+    };
+
+    let header = ["Tower", "Damage", "Kills", "Shots", "Hits"].map(String::from);
+    let rows = towers.iter().take(TOWER_TABLE_ROWS).map(|(name, stats)| {
+        [
+            name.clone(),
+            stats.damage_dealt.to_string(),
+            stats.kills.to_string(),
+            stats.shots_fired.to_string(),
+            stats.hits.to_string(),
+        ]
+    });
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                align_self: AlignSelf::Center,
+                flex_direction: FlexDirection::Column,
+                margin: UiRect::all(Val::Percent(1.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|commands| {
+            for row in std::iter::once(header).chain(rows) {
+                commands
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|commands| {
+                        for (column, cell) in row.into_iter().enumerate() {
+                            let width = if column == 0 { 180.0 } else { 90.0 };
+                            commands.spawn(TextBundle {
+                                style: Style {
+                                    size: Size::new(Val::Px(width), Val::Auto),
+                                    ..default()
+                                },
+                                text: Text::from_section(cell, style.clone()),
+                                ..default()
+                            });
+                        }
+                    });
+            }
+
+            if towers.len() > TOWER_TABLE_ROWS {
+                commands.spawn(TextBundle::from_section(
+                    format!("and {} more", towers.len() - TOWER_TABLE_ROWS),
+                    style.clone(),
+                ));
+            }
+        });
+}
+
+fn restart_button_clicked(
+    interactions: Query<&Interaction, (With<RestartButton>, Changed<Interaction>)>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut mouse_input: ResMut<Input<MouseButton>>,
+) {
+    for interaction in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            game_state.set(GameState::GamePlay);
+            mouse_input.clear();
+        }
+    }
+}
+
+fn menu_button_clicked(
+    interactions: Query<&Interaction, (With<MenuButton>, Changed<Interaction>)>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut mouse_input: ResMut<Input<MouseButton>>,
+) {
+    for interaction in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            game_state.set(GameState::MainMenu);
+            mouse_input.clear();
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::*;
+use std::time::Duration;
+
+/// Speeds offered by the HUD buttons and their hotkeys
+pub const GAME_SPEEDS: [(f32, KeyCode); 3] =
+    [(1.0, KeyCode::F1), (2.0, KeyCode::F2), (4.0, KeyCode::F3)];
+
+/// Gameplay clock running at `speed` times real time. The fixed simulation runs `speed`
+/// ticks per real tick, and effects outside it read this instead of `Time`, while UI and
+/// the camera stay at real time
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct GameTime {
+    pub speed: f32,
+    delta: Duration,
+}
+
+impl Default for GameTime {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            delta: Duration::ZERO,
+        }
+    }
+}
+
+impl GameTime {
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+}
+
+pub struct GameSpeedPlugin;
+
+impl Plugin for GameSpeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GameTime>()
+            .init_resource::<GameTime>()
+            .add_system(update_game_time.in_base_set(CoreSet::PreUpdate))
+            .add_system(reset_game_speed.in_schedule(OnEnter(GameState::GamePlay)))
+            .add_system(spawn_speed_ui.in_schedule(OnEnter(GameState::GamePlay)))
+            .add_systems(
+                (speed_hotkeys, speed_button_clicked)
+                    .in_set(FrameSet::Input)
+                    .in_set(OnUpdate(GameState::GamePlay)),
+            )
+            .add_system(
+                update_speed_buttons
+                    .in_set(FrameSet::Ui)
+                    .in_set(OnUpdate(GameState::GamePlay)),
+            );
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct SpeedUIRoot;
+
+#[derive(Component, Reflect)]
+pub struct SpeedButton(pub f32);
+
+fn update_game_time(
+    mut game_time: ResMut<GameTime>,
+    mut fixed_time: ResMut<FixedTime>,
+    time: Res<Time>,
+) {
+    game_time.delta = time.delta().mul_f32(game_time.speed);
+
+    // `FixedUpdate` already accumulates real time, top it up with the rest
+    fixed_time.tick(time.delta().mul_f32((game_time.speed - 1.0).max(0.0)));
+}
+
+fn reset_game_speed(mut game_time: ResMut<GameTime>) {
+    game_time.speed = 1.0;
+}
+
+fn spawn_speed_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Percent(1.0),
+                    bottom: Val::Percent(1.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(SpeedUIRoot)
+        .insert(GamePlayEntity)
+        .with_children(|commands| {
+            for (speed, _) in GAME_SPEEDS {
+                commands
+                    .spawn(ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(64.0), Val::Px(40.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::all(Val::Px(4.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(SpeedButton(speed))
+                    .with_children(|commands| {
+                        commands.spawn(TextBundle::from_section(
+                            format!("{speed}x"),
+                            TextStyle {
+                                font: asset_server.load("FiraSans-Bold.ttf"),
+                                font_size: 28.0,
+                                color: Color::BLACK,
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn speed_hotkeys(keyboard: Res<Input<KeyCode>>, mut game_time: ResMut<GameTime>) {
+    for (speed, key) in GAME_SPEEDS {
+        if keyboard.just_pressed(key) {
+            game_time.speed = speed;
+        }
+    }
+}
+
+fn speed_button_clicked(
+    interactions: Query<(&Interaction, &SpeedButton), Changed<Interaction>>,
+    mut game_time: ResMut<GameTime>,
+) {
+    for (interaction, button) in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            game_time.speed = button.0;
+        }
+    }
+}
+
+fn update_speed_buttons(
+    mut buttons: Query<(&SpeedButton, &mut BackgroundColor)>,
+    game_time: Res<GameTime>,
+) {
+    for (button, mut background) in &mut buttons {
+        *background = if button.0 == game_time.speed {
+            Color::ORANGE.into()
+        } else {
+            Color::WHITE.into()
+        };
+    }
+}
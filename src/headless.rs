@@ -0,0 +1,363 @@
+use crate::*;
+use bevy::{asset::AssetPlugin, math::Vec3Swizzles, scene::SceneSpawner, utils::HashMap};
+
+/// Fixed ticks run per app update, as fast as the machine allows
+const TICKS_PER_UPDATE: u32 = 64;
+
+/// Largest fraction by which a seeded run speeds up or slows down a wave's spawns
+const SPAWN_JITTER: f32 = 0.25;
+
+/// Multipliers on a tower type's stats from `TowerType::get_tower` and on its cost
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TowerTuning {
+    /// Shots per second, so `2.0` halves the shooting interval
+    pub fire_rate: f32,
+    pub range: f32,
+    pub bullet_speed: f32,
+    pub cost: f32,
+}
+
+impl Default for TowerTuning {
+    fn default() -> Self {
+        Self {
+            fire_rate: 1.0,
+            range: 1.0,
+            bullet_speed: 1.0,
+            cost: 1.0,
+        }
+    }
+}
+
+impl TowerTuning {
+    pub fn apply(&self, tower: &mut Tower) {
+        let interval = tower.shooting_timer.duration().div_f32(self.fire_rate);
+        tower.shooting_timer.set_duration(interval);
+        tower.range *= self.range;
+        tower.bullet_speed *= self.bullet_speed;
+
+        // Beams have no shooting interval, so fire rate scales their damage instead
+        if let AttackMode::Beam {
+            damage_per_second, ..
+        } = &mut tower.attack
+        {
+            *damage_per_second *= self.fire_rate;
+        }
+    }
+
+    pub fn cost(&self, tower_type: TowerType) -> u32 {
+        (tower_type.cost() as f32 * self.cost).round() as u32
+    }
+}
+
+/// A level to play without a window, building towers from a script
+#[derive(Resource, Clone, Debug)]
+pub struct SimulationConfig {
+    /// Index into `Levels`
+    pub level: usize,
+    /// Towers and where to put them on the ground, each built as soon as the player can
+    /// afford it
+    pub build_order: Vec<(TowerType, Vec2)>,
+    /// Money the player starts the run with
+    pub starting_money: u32,
+    /// Runs still going after this many seconds of game time are stopped
+    pub max_seconds: f32,
+    /// Seconds of game time between samples of the player's money
+    pub sample_interval: f32,
+    /// Tower types played with different stats, the rest use `get_tower` as is
+    pub tuning: HashMap<TowerType, TowerTuning>,
+    /// Jitters each wave's spawn interval by up to `SPAWN_JITTER` so runs with different
+    /// seeds see targets bunch up differently. `None` plays the level as designed
+    pub seed: Option<u32>,
+}
+
+impl SimulationConfig {
+    pub fn tuning(&self, tower_type: TowerType) -> TowerTuning {
+        self.tuning.get(&tower_type).copied().unwrap_or_default()
+    }
+
+    /// The waves of `level`, jittered by `seed`
+    pub fn waves(&self) -> Vec<Wave> {
+        let mut waves = Levels::default().levels[self.level].waves.clone();
+        if let Some(seed) = self.seed {
+            // Xorshift like `ParticleRng`, which must not start from zero
+            let mut state = seed.wrapping_mul(0x9E37_79B9) | 1;
+            for wave in &mut waves {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                let signed = (state as f32 / u32::MAX as f32) * 2.0 - 1.0;
+                wave.interval *= 1.0 + signed * SPAWN_JITTER;
+            }
+        }
+        waves
+    }
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            level: 0,
+            build_order: vec![
+                (TowerType::Potato, Vec2::new(2.0, 0.0)),
+                (TowerType::Tomato, Vec2::new(4.0, 0.0)),
+                (TowerType::Cabbage, Vec2::new(6.0, 0.0)),
+            ],
+            starting_money: STARTING_MONEY,
+            max_seconds: 3600.0,
+            sample_interval: 10.0,
+            tuning: HashMap::default(),
+            seed: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Victory,
+    GameOver,
+    TimedOut,
+}
+
+#[derive(Clone, Debug)]
+pub struct TowerReport {
+    pub tower_type: TowerType,
+    pub position: Vec2,
+    /// Seconds of game time into the run when the tower went up
+    pub built_at: f32,
+    /// What the player paid, after tuning
+    pub cost: u32,
+    pub stats: TowerStats,
+}
+
+impl TowerReport {
+    /// Damage per second over the time the tower stood, per unit of money spent on it
+    pub fn dps_per_cost(&self, run_seconds: f32) -> f32 {
+        let standing = run_seconds - self.built_at;
+        if standing <= 0.0 || self.cost == 0 {
+            0.0
+        } else {
+            self.stats.damage_dealt as f32 / standing / self.cost as f32
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug, Default)]
+pub struct SimulationReport {
+    /// Set once the run has ended
+    pub outcome: Option<Outcome>,
+    pub ticks: u64,
+    pub seconds: f32,
+    /// Targets the level sends over all its waves
+    pub targets: u32,
+    pub leaks: u32,
+    /// The player's money sampled over the run, as `(seconds, money)`
+    pub money: Vec<(f32, u32)>,
+    /// Seconds into the run at which each wave was cleared
+    pub wave_clear_times: Vec<f32>,
+    pub towers: Vec<TowerReport>,
+    pub timed_out: bool,
+}
+
+impl SimulationReport {
+    /// Fraction of the level's targets that reached the end of the path
+    pub fn leak_rate(&self) -> f32 {
+        if self.targets == 0 {
+            0.0
+        } else {
+            self.leaks as f32 / self.targets as f32
+        }
+    }
+}
+
+#[derive(Resource)]
+struct BuildScript {
+    next: usize,
+    /// Towers built so far with the time they went up and what they cost
+    built: Vec<(Entity, TowerType, Vec2, f32, u32)>,
+}
+
+/// An app running the gameplay plugins with `MinimalPlugins`, with no window, rendering,
+/// audio or input. Models are never loaded, so `GameAssets` holds default handles
+pub fn headless_app(config: SimulationConfig) -> App {
+    let waves = config.waves();
+    let mut levels = Levels {
+        current: config.level,
+        ..default()
+    };
+    levels.levels[config.level].waves = waves.clone();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin::default())
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<Scene>()
+        // Rapier's async scene colliders ask for this even though none are used
+        .init_resource::<SceneSpawner>()
+        .add_state::<GameState>()
+        .add_plugins(GameplayPlugins)
+        .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
+        .insert_resource(GameAssets::default())
+        .insert_resource(levels)
+        .insert_resource(BuildScript {
+            next: 0,
+            built: Vec::new(),
+        })
+        .insert_resource(config)
+        .insert_resource(SimulationReport {
+            targets: waves.iter().map(|wave| wave.count).sum(),
+            ..default()
+        })
+        .add_startup_system(start_simulation)
+        .add_system(advance_fixed_time.in_base_set(CoreSet::PreUpdate))
+        .add_system(spawn_wall_colliders.in_schedule(OnEnter(GameState::GamePlay)))
+        .add_systems(
+            (fund_player, build_towers.after(fund_player))
+                .in_set(SimulationSet::Spawn)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(
+            record_tick
+                .in_set(SimulationSet::Record)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(finish_report.in_schedule(OnExit(GameState::GamePlay)));
+    app
+}
+
+/// Plays `config` to the end and reports how it went
+pub fn run_simulation(config: SimulationConfig) -> SimulationReport {
+    let mut app = headless_app(config);
+    while app.world.resource::<SimulationReport>().outcome.is_none() {
+        app.update();
+    }
+    app.world.resource::<SimulationReport>().clone()
+}
+
+fn start_simulation(mut game_state: ResMut<NextState<GameState>>) {
+    game_state.set(GameState::GamePlay);
+}
+
+/// Runs a batch of ticks every update regardless of how long the update took
+fn advance_fixed_time(mut fixed_time: ResMut<FixedTime>) {
+    let period = fixed_time.period;
+    fixed_time.tick(period * TICKS_PER_UPDATE);
+}
+
+/// The walls only need their colliders to block line of sight
+fn spawn_wall_colliders(mut commands: Commands) {
+    for (position, half_extents) in WALLS {
+        commands.spawn((
+            TransformBundle::from_transform(Transform::from_translation(position)),
+            RigidBody::Fixed,
+            Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            GamePlayEntity,
+            Name::new("Wall"),
+        ));
+    }
+}
+
+/// Hands the player the configured money on the first tick, once they have spawned
+fn fund_player(mut players: Query<&mut Player, Added<Player>>, config: Res<SimulationConfig>) {
+    for mut player in &mut players {
+        player.money = config.starting_money;
+    }
+}
+
+fn build_towers(
+    mut commands: Commands,
+    mut script: ResMut<BuildScript>,
+    mut player: Query<&mut Player>,
+    config: Res<SimulationConfig>,
+    report: Res<SimulationReport>,
+    assets: Res<GameAssets>,
+) {
+    let mut player = player.single_mut();
+
+    while let Some(&(tower_type, position)) = config.build_order.get(script.next) {
+        let tuning = config.tuning(tower_type);
+        let cost = tuning.cost(tower_type);
+        if player.money < cost {
+            break;
+        }
+        player.money -= cost;
+
+        let entity = spawn_tower(
+            &mut commands,
+            &assets,
+            position.extend(TOWER_HEIGHT).xzy(),
+            tower_type,
+        );
+        let (_, mut tower) = tower_type.get_tower(&assets);
+        tuning.apply(&mut tower);
+        commands.entity(entity).insert(tower);
+
+        script
+            .built
+            .push((entity, tower_type, position, report.seconds, cost));
+        script.next += 1;
+    }
+}
+
+fn record_tick(
+    mut report: ResMut<SimulationReport>,
+    mut leaks: EventReader<TargetLeakEvent>,
+    player: Query<&Player>,
+    waves: Res<Waves>,
+    config: Res<SimulationConfig>,
+    fixed_time: Res<FixedTime>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    report.ticks += 1;
+    report.seconds = report.ticks as f32 * fixed_time.period.as_secs_f32();
+    let seconds = report.seconds;
+
+    report.leaks += leaks.iter().count() as u32;
+
+    while report.wave_clear_times.len() < waves.cleared {
+        report.wave_clear_times.push(seconds);
+    }
+
+    let due = report.money.last().map_or(true, |(sampled_at, _)| {
+        seconds - sampled_at >= config.sample_interval
+    });
+    if due {
+        report.money.push((seconds, player.single().money));
+    }
+
+    if seconds >= config.max_seconds {
+        report.timed_out = true;
+        game_state.set(GameState::GameOver);
+    }
+}
+
+fn finish_report(
+    mut report: ResMut<SimulationReport>,
+    game_state: Res<State<GameState>>,
+    script: Res<BuildScript>,
+    towers: Query<&TowerStats>,
+) {
+    // While `OnExit` runs, `State` already holds the state being entered
+    report.outcome = Some(if report.timed_out {
+        Outcome::TimedOut
+    } else if game_state.0 == GameState::Victory {
+        Outcome::Victory
+    } else {
+        Outcome::GameOver
+    });
+
+    report.towers = script
+        .built
+        .iter()
+        .filter_map(|&(entity, tower_type, position, built_at, cost)| {
+            towers.get(entity).ok().map(|stats| TowerReport {
+                tower_type,
+                position,
+                built_at,
+                cost,
+                stats: stats.clone(),
+            })
+        })
+        .collect();
+}
@@ -0,0 +1,196 @@
+use crate::*;
+
+#[derive(Reflect, Clone, Debug)]
+pub struct Level {
+    pub name: String,
+    pub waves: Vec<Wave>,
+}
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct Levels {
+    pub levels: Vec<Level>,
+    /// Index of the level being played or last played
+    pub current: usize,
+    /// How many levels from the start of the list can be played
+    pub unlocked: usize,
+    /// Best star rating earned on each level, 0 if it has not been won
+    pub best_stars: Vec<u32>,
+}
+
+impl Levels {
+    pub fn new(levels: Vec<Level>) -> Self {
+        Self {
+            best_stars: vec![0; levels.len()],
+            levels,
+            current: 0,
+            unlocked: 1,
+        }
+    }
+
+    pub fn current(&self) -> &Level {
+        &self.levels[self.current]
+    }
+
+    /// Records a win on the current level and unlocks the one after it
+    pub fn complete_current(&mut self, stars: u32) {
+        let best = &mut self.best_stars[self.current];
+        *best = (*best).max(stars);
+        self.unlocked = self.unlocked.max(self.current + 2).min(self.levels.len());
+    }
+}
+
+impl Default for Levels {
+    fn default() -> Self {
+        let wave = |count, interval, health, speed| Wave {
+            count,
+            interval,
+            health,
+            speed,
+        };
+
+        Self::new(vec![
+            Level {
+                name: "Meadow".into(),
+                waves: vec![
+                    wave(10, 1.0, 3, 0.3),
+                    wave(15, 0.8, 4, 0.35),
+                    wave(20, 0.6, 5, 0.4),
+                ],
+            },
+            Level {
+                name: "Orchard".into(),
+                waves: vec![
+                    wave(15, 0.8, 4, 0.35),
+                    wave(20, 0.6, 5, 0.4),
+                    wave(25, 0.5, 6, 0.45),
+                    wave(30, 0.4, 6, 0.5),
+                ],
+            },
+            Level {
+                name: "Harvest".into(),
+                waves: vec![
+                    wave(20, 0.6, 5, 0.4),
+                    wave(25, 0.5, 6, 0.45),
+                    wave(30, 0.4, 7, 0.5),
+                    wave(35, 0.35, 8, 0.55),
+                    wave(40, 0.3, 10, 0.6),
+                ],
+            },
+        ])
+    }
+}
+
+pub struct LevelSelectPlugin;
+
+impl Plugin for LevelSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Levels>()
+            .init_resource::<Levels>()
+            .add_system(spawn_level_select.in_schedule(OnEnter(GameState::LevelSelect)))
+            .add_system(
+                despawn_with::<LevelSelectUIRoot>.in_schedule(OnExit(GameState::LevelSelect)),
+            )
+            .add_systems(
+                (level_button_clicked, back_button_clicked)
+                    .in_set(OnUpdate(GameState::LevelSelect)),
+            );
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct LevelSelectUIRoot;
+
+#[derive(Component, Reflect)]
+pub struct LevelButton(pub usize);
+
+#[derive(Component, Reflect)]
+pub struct BackButton;
+
+fn spawn_level_select(mut commands: Commands, asset_server: Res<AssetServer>, levels: Res<Levels>) {
+    let buttons = levels
+        .levels
+        .iter()
+        .enumerate()
+        .map(|(index, level)| {
+            let (text, color) = if index < levels.unlocked {
+                (
+                    format!(
+                        "{}. {} ({}/3 stars)",
+                        index + 1,
+                        level.name,
+                        levels.best_stars[index]
+                    ),
+                    Color::RED,
+                )
+            } else {
+                (format!("{}. Locked", index + 1), Color::GRAY)
+            };
+
+            let button = spawn_button(&mut commands, &asset_server, &text, color);
+            commands.entity(button).insert(LevelButton(index));
+            button
+        })
+        .collect::<Vec<_>>();
+
+    let back_button = spawn_button(&mut commands, &asset_server, "Back", Color::BLUE);
+    commands.entity(back_button).insert(BackButton);
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(LevelSelectUIRoot)
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(2.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    "Select Level",
+                    TextStyle {
+                        font: asset_server.load("FiraSans-Bold.ttf"),
+                        font_size: 72.0,
+                        color: Color::BLACK,
+                    },
+                ),
+                ..default()
+            });
+        })
+        .push_children(&buttons)
+        .add_child(back_button);
+}
+
+fn level_button_clicked(
+    interactions: Query<(&Interaction, &LevelButton), Changed<Interaction>>,
+    mut levels: ResMut<Levels>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut mouse_input: ResMut<Input<MouseButton>>,
+) {
+    for (interaction, button) in &interactions {
+        if matches!(interaction, Interaction::Clicked) && button.0 < levels.unlocked {
+            levels.current = button.0;
+            game_state.set(GameState::GamePlay);
+            mouse_input.clear();
+        }
+    }
+}
+
+fn back_button_clicked(
+    interactions: Query<&Interaction, (With<BackButton>, Changed<Interaction>)>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    for interaction in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            game_state.set(GameState::MainMenu);
+        }
+    }
+}
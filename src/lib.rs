@@ -1,28 +1,95 @@
-use bevy::prelude::*;
-use bevy_mod_picking::Selection;
+use bevy::{app::PluginGroupBuilder, pbr::NotShadowCaster, prelude::*};
+use bevy_mod_picking::{Highlighting, PickableBundle, Selection};
+use bevy_rapier3d::prelude::*;
 
+pub mod abilities;
+pub mod beam;
+pub mod build_preview;
 pub mod bullet;
+pub mod cleanup;
+pub mod game_over;
+pub mod game_speed;
+pub mod headless;
+pub mod levels;
 pub mod main_menu;
+pub mod particles;
+pub mod pause;
+pub mod placement;
 pub mod player;
+pub mod projectile_pool;
+pub mod simulation;
+pub mod spatial;
 pub mod target;
 pub mod tower;
+pub mod veterancy;
+pub mod victory;
+pub mod waves;
 
+pub use abilities::*;
+pub use beam::*;
+pub use build_preview::*;
 pub use bullet::*;
+pub use cleanup::*;
+pub use game_over::*;
+pub use game_speed::*;
+pub use headless::*;
+pub use levels::*;
 pub use main_menu::*;
+pub use particles::*;
+pub use pause::*;
+pub use placement::*;
 pub use player::*;
+pub use projectile_pool::*;
+pub use simulation::*;
+pub use spatial::*;
 pub use target::*;
 pub use tower::*;
+pub use veterancy::*;
+pub use victory::*;
+pub use waves::*;
 
 pub const WIDTH: f32 = 1280.0;
 pub const HEIGHT: f32 = 720.0;
 
 pub const CUBE_COLOUR: Color = Color::rgb(0.67, 0.84, 0.92);
 
+/// Position and half extents of each wall blocking line of sight
+pub const WALLS: [(Vec3, Vec3); 2] = [
+    (Vec3::new(3.0, 0.75, 4.0), Vec3::new(1.0, 0.75, 0.15)),
+    (Vec3::new(-4.0, 0.75, -0.5), Vec3::new(1.5, 0.75, 0.15)),
+];
+
+/// Every plugin the gameplay simulation needs, without any rendering, UI, audio or input,
+/// so it can also run headless. Plugins that mix both are split into an `XPlugin` added
+/// here and an `XViewPlugin` with the presentation, which only the game adds
+pub struct GameplayPlugins;
+
+impl PluginGroup for GameplayPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(SimulationPlugin)
+            .add(PlayerPlugin)
+            .add(TargetPlugin)
+            .add(WavePlugin)
+            .add(TowerPlugin)
+            .add(BulletPlugin)
+            .add(BeamPlugin)
+            .add(AbilityPlugin)
+            .add(VeterancyPlugin)
+            .add(GameOverPlugin)
+            .add(VictoryPlugin)
+            .add(CleanupPlugin)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 pub enum GameState {
     #[default]
     MainMenu,
+    LevelSelect,
     GamePlay,
+    GameOver,
+    Victory,
 }
 
 #[derive(Resource, Default)]
@@ -106,6 +173,19 @@ pub fn camera_controls(
     }
 }
 
+/// Whether nothing solid blocks the straight line from `from` to `to`
+pub fn line_of_sight(rapier: &RapierContext, from: Vec3, to: Vec3) -> bool {
+    rapier
+        .cast_ray(
+            from,
+            to - from,
+            1.0,
+            true,
+            QueryFilter::only_fixed().exclude_sensors(),
+        )
+        .is_none()
+}
+
 // pub fn what_is_selected(selection: Query<(&Name, &Selection)>) {
 //     for (name, selection) in &selection {
 //         if selection.selected() {
@@ -117,14 +197,75 @@ pub fn camera_controls(
 #[derive(Component, Reflect)]
 pub struct TowerUIRoot;
 
-#[derive(Component, Clone, Copy, Debug)]
+#[derive(Component, Reflect)]
+pub struct TowerBase;
+
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum TowerType {
     Tomato,
     Potato,
     Cabbage,
+    /// Lobs tomatoes over walls onto groups of targets
+    Mortar,
+    /// Burns through one target at a time with a beam
+    Laser,
 }
 
 impl TowerType {
+    pub const ALL: [TowerType; 5] = [
+        TowerType::Tomato,
+        TowerType::Potato,
+        TowerType::Cabbage,
+        TowerType::Mortar,
+        TowerType::Laser,
+    ];
+
+    /// Colour of the splat this tower's vegetable leaves on impact
+    pub fn splat_colour(&self) -> Color {
+        use TowerType::*;
+        match self {
+            Tomato | Mortar => Color::rgb(0.85, 0.1, 0.1),
+            Potato => Color::rgb(0.8, 0.65, 0.4),
+            Cabbage | Laser => Color::rgb(0.45, 0.8, 0.3),
+        }
+    }
+
+    pub fn cost(&self) -> u32 {
+        use TowerType::*;
+        match self {
+            Tomato => 50,
+            Potato => 80,
+            Cabbage => 110,
+            Mortar => 90,
+            Laser => 120,
+        }
+    }
+
+    pub fn attack(&self) -> AttackMode {
+        self.get_tower(&GameAssets::default()).1.attack
+    }
+
+    /// Build button image, towers without their own artwork share their ammunition's
+    pub fn icon(&self) -> &'static str {
+        use TowerType::*;
+        match self {
+            Tomato | Mortar => "images/tomato_tower.png",
+            Potato => "images/potato_tower.png",
+            Cabbage | Laser => "images/cabbage_tower.png",
+        }
+    }
+
+    pub fn ability(&self) -> Ability {
+        use TowerType::*;
+        match self {
+            Tomato => Ability::new(AbilityKind::RapidFire, 20.0, 5.0),
+            Potato => Ability::new(AbilityKind::Volley, 15.0, 0.0),
+            Cabbage => Ability::new(AbilityKind::RapidFire, 25.0, 4.0),
+            Mortar => Ability::new(AbilityKind::Volley, 20.0, 0.0),
+            Laser => Ability::new(AbilityKind::Overcharge, 25.0, 4.0),
+        }
+    }
+
     fn get_tower(&self, assets: &GameAssets) -> (Handle<Scene>, Tower) {
         use TowerType::*;
         match self {
@@ -133,7 +274,9 @@ impl TowerType {
                 Tower {
                     shooting_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
                     bullet_offset: Vec3::new(0.0, 0.6, 0.0),
+                    bullet_speed: 3.5,
                     range: 4.5,
+                    attack: AttackMode::Projectile,
                 },
             ),
             Potato => (
@@ -141,7 +284,9 @@ impl TowerType {
                 Tower {
                     shooting_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
                     bullet_offset: Vec3::new(0.0, 0.6, 0.0),
+                    bullet_speed: 6.5,
                     range: 4.5,
+                    attack: AttackMode::Projectile,
                 },
             ),
             Cabbage => (
@@ -149,34 +294,89 @@ impl TowerType {
                 Tower {
                     shooting_timer: Timer::from_seconds(0.8, TimerMode::Repeating),
                     bullet_offset: Vec3::new(0.0, 0.6, 0.0),
+                    bullet_speed: 1.5,
+                    range: 4.5,
+                    attack: AttackMode::Projectile,
+                },
+            ),
+            Mortar => (
+                assets.tomato_tower_scene.clone(),
+                Tower {
+                    shooting_timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+                    bullet_offset: Vec3::new(0.0, 0.6, 0.0),
+                    bullet_speed: 3.5,
+                    range: 5.5,
+                    attack: AttackMode::Artillery {
+                        min_range: 1.5,
+                        blast_radius: 0.6,
+                    },
+                },
+            ),
+            Laser => (
+                assets.cabbage_tower_scene.clone(),
+                Tower {
+                    // Beams neither use the shooting timer nor fire bullets
+                    shooting_timer: Timer::default(),
+                    bullet_offset: Vec3::new(0.0, 0.6, 0.0),
+                    bullet_speed: 0.0,
                     range: 4.5,
+                    attack: AttackMode::Beam {
+                        damage_per_second: 1.0,
+                        ramp_per_second: 0.5,
+                        max_ramp: 3.0,
+                    },
                 },
             ),
         }
     }
 
-    fn get_bullet(&self, direction: Vec3, assets: &GameAssets) -> (Handle<Scene>, Bullet) {
+    /// Sensor shape roughly matching this tower's bullet model
+    pub fn bullet_collider(&self) -> Collider {
         use TowerType::*;
         match self {
-            Tomato => (
+            Tomato | Mortar => Collider::ball(0.12),
+            Potato => Collider::capsule_z(0.06, 0.06),
+            Cabbage | Laser => Collider::cuboid(0.15, 0.05, 0.15),
+        }
+    }
+
+    fn get_bullet(
+        &self,
+        direction: Vec3,
+        speed: f32,
+        owner: Entity,
+        assets: &GameAssets,
+    ) -> (Handle<Scene>, Bullet) {
+        use TowerType::*;
+        match self {
+            Tomato | Mortar => (
                 assets.tomato_scene.clone(),
                 Bullet {
                     direction,
-                    speed: 3.5,
+                    speed,
+                    damage: 1,
+                    tower_type: *self,
+                    owner,
                 },
             ),
             Potato => (
                 assets.potato_scene.clone(),
                 Bullet {
                     direction,
-                    speed: 6.5,
+                    speed,
+                    damage: 1,
+                    tower_type: *self,
+                    owner,
                 },
             ),
-            Cabbage => (
+            Cabbage | Laser => (
                 assets.cabbage_scene.clone(),
                 Bullet {
                     direction,
-                    speed: 1.5,
+                    speed,
+                    damage: 1,
+                    tower_type: *self,
+                    owner,
                 },
             ),
         }
@@ -184,16 +384,6 @@ impl TowerType {
 }
 
 pub fn create_ui(commands: &mut Commands, asset_server: &AssetServer) {
-    let towers = [TowerType::Tomato, TowerType::Potato, TowerType::Cabbage];
-
-    let costs = [50, 80, 110];
-
-    let button_icons: [Handle<Image>; 3] = [
-        asset_server.load("images/tomato_tower.png"),
-        asset_server.load("images/potato_tower.png"),
-        asset_server.load("images/cabbage_tower.png"),
-    ];
-
     commands
         .spawn(NodeBundle {
             style: Style {
@@ -205,8 +395,9 @@ pub fn create_ui(commands: &mut Commands, asset_server: &AssetServer) {
             ..default()
         })
         .insert(TowerUIRoot)
+        .insert(GamePlayEntity)
         .with_children(|commands| {
-            for i in 0..=2 {
+            for tower_type in TowerType::ALL {
                 commands
                     .spawn(ButtonBundle {
                         style: Style {
@@ -218,14 +409,14 @@ pub fn create_ui(commands: &mut Commands, asset_server: &AssetServer) {
                             margin: UiRect::all(Val::Percent(2.0)),
                             ..default()
                         },
-                        image: button_icons[i].clone().into(),
+                        image: asset_server.load(tower_type.icon()).into(),
                         ..default()
                     })
                     .insert(TowerButtonState {
-                        cost: costs[i],
+                        cost: tower_type.cost(),
                         affordable: false,
                     })
-                    .insert(towers[i]);
+                    .insert(tower_type);
             }
         });
 }
@@ -234,7 +425,7 @@ pub fn create_ui_on_selection(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     // TODO: Implement on change detection
-    selections: Query<&Selection>,
+    selections: Query<&Selection, With<TowerBase>>,
     root: Query<Entity, With<TowerUIRoot>>,
 ) {
     let at_least_one_selected = selections.iter().any(|selection| selection.selected());
@@ -257,20 +448,39 @@ pub fn create_ui_on_selection(
 pub fn tower_button_clicked(
     interactions: Query<(&Interaction, &TowerType, &TowerButtonState), Changed<Interaction>>,
     mut commands: Commands,
-    selection: Query<(Entity, &Selection, &Transform)>,
+    selection: Query<
+        (
+            Entity,
+            &Selection,
+            &Transform,
+            &Handle<Mesh>,
+            &Highlighting<StandardMaterial>,
+        ),
+        With<TowerBase>,
+    >,
     mut player: Query<&mut Player>,
     assets: Res<GameAssets>,
 ) {
     let mut player = player.single_mut();
     for (interaction, tower_type, button_state) in &interactions {
         if matches!(interaction, Interaction::Clicked) {
-            for (entity, selection, transform) in &selection {
+            for (entity, selection, transform, mesh, highlighting) in &selection {
                 if selection.selected() && player.money >= button_state.cost {
                     player.money -= button_state.cost;
 
                     commands.entity(entity).despawn_recursive();
 
-                    spawn_tower(&mut commands, &assets, transform.translation, *tower_type);
+                    let tower =
+                        spawn_tower(&mut commands, &assets, transform.translation, *tower_type);
+
+                    // Keep the base's pick collider so the built tower can be selected
+                    commands
+                        .entity(tower)
+                        .insert(mesh.clone())
+                        .insert(highlighting.initial.clone())
+                        .insert(highlighting.clone())
+                        .insert(NotShadowCaster)
+                        .insert(PickableBundle::default());
                 }
             }
         }
@@ -1,6 +1,7 @@
 use bevy::{pbr::NotShadowCaster, prelude::*, window::WindowResolution};
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_mod_picking::*;
+use bevy_rapier3d::prelude::*;
 use tower_game::*;
 
 fn main() {
@@ -16,12 +17,28 @@ fn main() {
         }))
         .add_state::<GameState>()
         .add_plugin(WorldInspectorPlugin::new())
+        .add_plugins(GameplayPlugins)
         .add_plugin(MainMenuPlugin)
-        .add_plugin(PlayerPlugin)
-        .add_plugin(TowerPlugin)
-        .add_plugin(BulletPlugin)
-        .add_plugin(TargetPlugin)
+        .add_plugin(PlayerViewPlugin)
+        .add_plugin(TowerViewPlugin)
+        .add_plugin(BeamViewPlugin)
+        .add_plugin(ParticlePlugin)
+        .add_plugin(GameOverViewPlugin)
+        .add_plugin(VictoryViewPlugin)
+        .add_plugin(LevelSelectPlugin)
+        .add_plugin(PausePlugin)
+        .add_plugin(GameSpeedPlugin)
+        .add_plugin(PlacementPlugin)
+        .add_plugin(BuildPreviewPlugin)
+        .add_plugin(VeterancyViewPlugin)
+        .add_plugin(AbilityViewPlugin)
         .add_plugins(DefaultPickingPlugins)
+        .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
+        // Set `DebugRenderContext::enabled` to see the colliders
+        .add_plugin(RapierDebugRenderPlugin {
+            enabled: false,
+            ..default()
+        })
         .insert_resource(ClearColor(Color::rgb(0.2, 0.2, 0.2)))
         .insert_resource(GameAssets::default())
         .add_startup_system(asset_loading.in_base_set(StartupSet::PreStartup))
@@ -51,44 +68,44 @@ fn spawn_basic_scene(
     mut materials: ResMut<Assets<StandardMaterial>>,
     game_assets: Res<GameAssets>,
 ) {
-    for i in 1..=10 {
-        commands
-            .spawn(SceneBundle {
-                scene: game_assets.target_scene.clone(),
-                transform: Transform::from_xyz(-i as f32, 0.2, 1.5),
-                ..default()
-            })
-            .insert(Target {
-                speed: 0.3,
-                ..default()
-            })
-            .insert(Health { value: 3 })
-            .insert(Name::new("Target"));
-
-        // // Row 2
-        // commands
-        //     .spawn(PbrBundle {
-        //         mesh: meshes.add(Mesh::from(shape::Cube { size: 0.4 })),
-        //         material: materials.add(CUBE_COLOUR.into()),
-        //         transform: Transform::from_xyz(-2.0 * i as f32, 1.0, 1.5),
-        //         ..default()
-        //     })
-        //     .insert(Target { speed: 0.35 })
-        //     .insert(Health { value: 3 })
-        //     .insert(Name::new("Target"));
-    }
-
     commands
         .spawn(PbrBundle {
             mesh: meshes.add(Mesh::from(shape::Plane {
-                size: 50.0,
+                size: MAP_HALF_EXTENT * 2.0,
                 ..default()
             })),
             material: materials.add(Color::SEA_GREEN.into()),
             ..default()
         })
+        .insert(RigidBody::Fixed)
+        .insert(Collider::cuboid(MAP_HALF_EXTENT, 0.01, MAP_HALF_EXTENT))
+        .insert(GamePlayEntity)
         .insert(Name::new("Ground"));
 
+    let wall_colour = materials.add(Color::GRAY.into());
+
+    for (position, half_extents) in WALLS {
+        commands
+            .spawn(PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Box::new(
+                    half_extents.x * 2.0,
+                    half_extents.y * 2.0,
+                    half_extents.z * 2.0,
+                ))),
+                material: wall_colour.clone(),
+                transform: Transform::from_translation(position),
+                ..default()
+            })
+            .insert(RigidBody::Fixed)
+            .insert(Collider::cuboid(
+                half_extents.x,
+                half_extents.y,
+                half_extents.z,
+            ))
+            .insert(GamePlayEntity)
+            .insert(Name::new("Wall"));
+    }
+
     let default_collider_colour = materials.add(Color::rgba(0.3, 0.5, 0.3, 0.3).into());
     let selected_collider_colour = materials.add(Color::rgba(0.3, 0.9, 0.3, 0.9).into());
 
@@ -100,6 +117,8 @@ fn spawn_basic_scene(
                 0.0,
             )))
             .insert(Name::new("Tower Base"))
+            .insert(TowerBase)
+            .insert(GamePlayEntity)
             .insert(meshes.add(shape::Capsule::default().into()))
             .insert(Highlighting {
                 initial: default_collider_colour.clone(),
@@ -168,5 +187,6 @@ fn spawn_basic_scene(
             transform: Transform::from_xyz(4.0, 8.0, 4.0),
             ..default()
         })
+        .insert(GamePlayEntity)
         .insert(Name::new("Light"));
 }
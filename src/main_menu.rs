@@ -7,6 +7,7 @@ pub struct MainMenuPlugin;
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_system((spawn_main_menu).in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(despawn_with::<MenuUIRoot>.in_schedule(OnExit(GameState::MainMenu)))
             .add_systems(
                 (start_button_clicked, quit_button_clicked).in_set(OnUpdate(GameState::MainMenu)),
             );
@@ -61,7 +62,7 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         .add_child(quit_button);
 }
 
-fn spawn_button(
+pub fn spawn_button(
     commands: &mut Commands,
     asset_server: &AssetServer,
     text: &str,
@@ -101,17 +102,13 @@ fn spawn_button(
 }
 
 fn start_button_clicked(
-    mut commands: Commands,
     interactions: Query<&Interaction, (With<StartButton>, Changed<Interaction>)>,
-    menu_root: Query<Entity, With<MenuUIRoot>>,
     mut game_state: ResMut<NextState<GameState>>,
     mut mouse_input: ResMut<Input<MouseButton>>,
 ) {
     for interaction in &interactions {
         if matches!(interaction, Interaction::Clicked) {
-            let root_entity = menu_root.single();
-            commands.entity(root_entity).despawn_recursive();
-            game_state.set(GameState::GamePlay);
+            game_state.set(GameState::LevelSelect);
             mouse_input.clear()
         }
     }
@@ -0,0 +1,183 @@
+use crate::*;
+use bevy::utils::HashMap;
+
+/// Downward acceleration applied to particles
+const PARTICLE_GRAVITY: f32 = 6.0;
+
+#[derive(Component, Reflect)]
+pub struct Particle {
+    pub velocity: Vec3,
+    pub lifetime: Timer,
+}
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct ParticleSettings {
+    /// Bursts are cut short once this many particles are alive
+    pub max_particles: usize,
+}
+
+impl Default for ParticleSettings {
+    fn default() -> Self {
+        Self { max_particles: 400 }
+    }
+}
+
+/// Particles share one material per splat colour and fade out by shrinking
+#[derive(Resource)]
+pub struct ParticleAssets {
+    pub mesh: Handle<Mesh>,
+    pub splat_materials: HashMap<TowerType, Handle<StandardMaterial>>,
+    pub death_material: Handle<StandardMaterial>,
+}
+
+/// Cheap xorshift generator so bursts scatter without pulling in a rand crate
+#[derive(Resource)]
+pub struct ParticleRng(pub u32);
+
+impl Default for ParticleRng {
+    fn default() -> Self {
+        Self(0x9E37_79B9)
+    }
+}
+
+impl ParticleRng {
+    /// Uniform value in `[-1, 1]`
+    fn next_signed(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        (self.0 as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+}
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Particle>()
+            .register_type::<ParticleSettings>()
+            .init_resource::<ParticleSettings>()
+            .init_resource::<ParticleRng>()
+            .add_system(load_particle_assets.in_schedule(OnEnter(GameState::GamePlay)))
+            .add_systems((emit_particles, update_particles).in_set(OnUpdate(GameState::GamePlay)));
+    }
+}
+
+fn load_particle_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut material = |colour: Color| {
+        materials.add(StandardMaterial {
+            base_color: colour,
+            unlit: true,
+            ..default()
+        })
+    };
+
+    commands.insert_resource(ParticleAssets {
+        mesh: meshes.add(
+            shape::Icosphere {
+                radius: 0.04,
+                subdivisions: 1,
+            }
+            .try_into()
+            .unwrap(),
+        ),
+        splat_materials: TowerType::ALL
+            .into_iter()
+            .map(|tower_type| (tower_type, material(tower_type.splat_colour())))
+            .collect(),
+        death_material: material(Color::rgb(0.3, 0.3, 0.35)),
+    });
+}
+
+/// A single splat burst waiting to be spawned
+struct Burst {
+    position: Vec3,
+    material: Handle<StandardMaterial>,
+    count: usize,
+    speed: f32,
+}
+
+fn emit_particles(
+    mut commands: Commands,
+    mut hits: EventReader<BulletHitEvent>,
+    mut deaths: EventReader<TargetDeathEvent>,
+    particles: Query<(), With<Particle>>,
+    settings: Res<ParticleSettings>,
+    assets: Res<ParticleAssets>,
+    mut rng: ResMut<ParticleRng>,
+) {
+    let bursts = hits
+        .iter()
+        .map(|hit| Burst {
+            position: hit.impact,
+            material: assets.splat_materials[&hit.tower_type].clone(),
+            count: 8,
+            speed: 1.5,
+        })
+        .chain(deaths.iter().map(|death| Burst {
+            position: death.position + Vec3::Y * 0.2,
+            material: assets.death_material.clone(),
+            count: 20,
+            speed: 2.5,
+        }));
+
+    let mut budget = settings
+        .max_particles
+        .saturating_sub(particles.iter().count());
+
+    for burst in bursts {
+        for _ in 0..burst.count.min(budget) {
+            let direction = Vec3::new(
+                rng.next_signed(),
+                rng.next_signed().abs() + 0.5,
+                rng.next_signed(),
+            )
+            .normalize();
+            let lifetime = 0.5 + 0.3 * rng.next_signed().abs();
+
+            commands
+                .spawn(PbrBundle {
+                    mesh: assets.mesh.clone(),
+                    material: burst.material.clone(),
+                    transform: Transform::from_translation(burst.position),
+                    ..default()
+                })
+                .insert(Particle {
+                    velocity: direction * burst.speed,
+                    lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
+                })
+                .insert(GamePlayEntity);
+        }
+        budget = budget.saturating_sub(burst.count);
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform)>,
+    time: Res<GameTime>,
+) {
+    for (entity, mut particle, mut transform) in &mut particles {
+        particle.lifetime.tick(time.delta());
+        if particle.lifetime.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        particle.velocity.y -= PARTICLE_GRAVITY * time.delta_seconds();
+        transform.translation += particle.velocity * time.delta_seconds();
+
+        // Splats stick where they land
+        if transform.translation.y < 0.0 {
+            transform.translation.y = 0.0;
+            particle.velocity = Vec3::ZERO;
+        }
+
+        transform.scale = Vec3::splat(1.0 - particle.lifetime.percent());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::*;
+
+/// Kept separate from `GameState` so pausing doesn't run the `GamePlay` enter and exit
+/// schedules
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct GameSettings {
+    /// Sound effect volume between 0 and 1
+    pub volume: f32,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self { volume: 1.0 }
+    }
+}
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GameSettings>()
+            .init_resource::<GameSettings>()
+            // Every gameplay system, and so every gameplay timer, stops while paused
+            .configure_set(OnUpdate(GameState::GamePlay).run_if(in_state(PauseState::Running)))
+            .add_system(toggle_pause.run_if(in_state(GameState::GamePlay)))
+            .add_system(unpause.in_schedule(OnExit(GameState::GamePlay)))
+            .add_system(spawn_pause_menu.in_schedule(OnEnter(PauseState::Paused)))
+            .add_system(despawn_pause_menu.in_schedule(OnExit(PauseState::Paused)))
+            .add_systems(
+                (
+                    resume_button_clicked,
+                    pause_restart_button_clicked,
+                    settings_button_clicked,
+                    quit_to_menu_button_clicked,
+                )
+                    .distributive_run_if(in_state(PauseState::Paused)),
+            );
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct PauseUIRoot;
+
+#[derive(Component, Reflect)]
+pub struct ResumeButton;
+
+#[derive(Component, Reflect)]
+pub struct PauseRestartButton;
+
+#[derive(Component, Reflect)]
+pub struct SettingsButton;
+
+#[derive(Component, Reflect)]
+pub struct QuitToMenuButton;
+
+fn toggle_pause(
+    keyboard: Res<Input<KeyCode>>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        next_pause_state.set(match pause_state.0 {
+            PauseState::Running => PauseState::Paused,
+            PauseState::Paused => PauseState::Running,
+        });
+    }
+}
+
+fn unpause(mut next_pause_state: ResMut<NextState<PauseState>>) {
+    next_pause_state.set(PauseState::Running);
+}
+
+fn volume_text(settings: &GameSettings) -> String {
+    format!("Volume: {:.0}%", settings.volume * 100.0)
+}
+
+fn spawn_pause_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+    mut physics: ResMut<RapierConfiguration>,
+) {
+    physics.physics_pipeline_active = false;
+
+    let resume_button = spawn_button(&mut commands, &asset_server, "Resume", Color::RED);
+    commands.entity(resume_button).insert(ResumeButton);
+    let restart_button = spawn_button(&mut commands, &asset_server, "Restart", Color::ORANGE);
+    commands.entity(restart_button).insert(PauseRestartButton);
+    let settings_button = spawn_button(
+        &mut commands,
+        &asset_server,
+        &volume_text(&settings),
+        Color::GREEN,
+    );
+    commands.entity(settings_button).insert(SettingsButton);
+    let quit_button = spawn_button(&mut commands, &asset_server, "Quit to Menu", Color::BLUE);
+    commands.entity(quit_button).insert(QuitToMenuButton);
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .insert(PauseUIRoot)
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(2.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    "Paused",
+                    TextStyle {
+                        font: asset_server.load("FiraSans-Bold.ttf"),
+                        font_size: 96.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                ..default()
+            });
+        })
+        .add_child(resume_button)
+        .add_child(restart_button)
+        .add_child(settings_button)
+        .add_child(quit_button);
+}
+
+fn despawn_pause_menu(
+    mut commands: Commands,
+    root: Query<Entity, With<PauseUIRoot>>,
+    mut physics: ResMut<RapierConfiguration>,
+) {
+    physics.physics_pipeline_active = true;
+
+    for entity in &root {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn resume_button_clicked(
+    interactions: Query<&Interaction, (With<ResumeButton>, Changed<Interaction>)>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    mut mouse_input: ResMut<Input<MouseButton>>,
+) {
+    for interaction in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            next_pause_state.set(PauseState::Running);
+            mouse_input.clear();
+        }
+    }
+}
+
+fn pause_restart_button_clicked(
+    interactions: Query<&Interaction, (With<PauseRestartButton>, Changed<Interaction>)>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut mouse_input: ResMut<Input<MouseButton>>,
+) {
+    for interaction in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            // Re-entering `GamePlay` runs its exit and enter schedules again, which
+            // also unpauses
+            game_state.set(GameState::GamePlay);
+            mouse_input.clear();
+        }
+    }
+}
+
+/// Steps the sound effect volume down through 100%, 50% and muted
+fn settings_button_clicked(
+    interactions: Query<(&Interaction, &Children), (With<SettingsButton>, Changed<Interaction>)>,
+    mut texts: Query<&mut Text>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, children) in &interactions {
+        if !matches!(interaction, Interaction::Clicked) {
+            continue;
+        }
+
+        settings.volume = if settings.volume > 0.5 {
+            0.5
+        } else if settings.volume > 0.0 {
+            0.0
+        } else {
+            1.0
+        };
+
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = volume_text(&settings);
+            }
+        }
+    }
+}
+
+fn quit_to_menu_button_clicked(
+    interactions: Query<&Interaction, (With<QuitToMenuButton>, Changed<Interaction>)>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut mouse_input: ResMut<Input<MouseButton>>,
+) {
+    for interaction in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            game_state.set(GameState::MainMenu);
+            mouse_input.clear();
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::*;
+use bevy::{ecs::system::SystemParam, math::Vec3Swizzles, window::PrimaryWindow};
+use bevy_mod_picking::{Highlighting, PickableBundle};
+use bevy_rapier3d::prelude::*;
+
+/// Half the side length of the square ground plane
+pub const MAP_HALF_EXTENT: f32 = 25.0;
+
+/// Footprint radius of a tower on the ground
+pub const TOWER_RADIUS: f32 = 0.5;
+
+/// How far a tower must stay from the centre line of the target path
+pub const PATH_CLEARANCE: f32 = 0.8;
+
+/// Height above the ground at which tower roots sit
+pub const TOWER_HEIGHT: f32 = 0.8;
+
+/// The tower type currently being placed, if any
+#[derive(Resource, Default)]
+pub struct Placement {
+    pub tower_type: Option<TowerType>,
+}
+
+#[derive(Resource)]
+pub struct PlacementAssets {
+    /// Shared by the preview and the pick collider of placed towers
+    pub preview_mesh: Handle<Mesh>,
+    pub valid_material: Handle<StandardMaterial>,
+    pub invalid_material: Handle<StandardMaterial>,
+    pub collider_material: Handle<StandardMaterial>,
+    pub selected_collider_material: Handle<StandardMaterial>,
+}
+
+#[derive(Component, Reflect, Default)]
+pub struct PlacementPreview {
+    pub position: Option<Vec3>,
+    pub valid: bool,
+}
+
+/// Where the cursor points on the ground plane
+#[derive(SystemParam)]
+pub struct GroundCursor<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<Camera3d>>,
+}
+
+impl GroundCursor<'_, '_> {
+    pub fn ground_point(&self) -> Option<Vec3> {
+        let (camera, camera_transform) = self.camera.single();
+
+        self.windows
+            .get_single()
+            .ok()
+            .and_then(|window| window.cursor_position())
+            .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+            .filter(|ray| ray.direction.y < 0.0)
+            .map(|ray| ray.origin - ray.direction * (ray.origin.y / ray.direction.y))
+    }
+}
+
+/// Everything a new tower must keep clear of
+#[derive(SystemParam)]
+pub struct PlacementRules<'w, 's> {
+    occupied: Query<'w, 's, &'static GlobalTransform, Or<(With<Tower>, With<TowerBase>)>>,
+    path: Res<'w, TargetPath>,
+    rapier: Res<'w, RapierContext>,
+}
+
+impl PlacementRules<'_, '_> {
+    /// Whether a tower could stand at `position`, at tower height
+    pub fn allows(&self, position: Vec3) -> bool {
+        // The tower footprint must not cut into walls or other solid scenery
+        let blocked = self
+            .rapier
+            .intersection_with_shape(
+                position,
+                Quat::IDENTITY,
+                &Collider::ball(TOWER_RADIUS),
+                QueryFilter::only_fixed().exclude_sensors(),
+            )
+            .is_some();
+
+        !blocked
+            && placement_is_valid(
+                position.xz(),
+                &self.path,
+                self.occupied.iter().map(|other| other.translation().xz()),
+            )
+    }
+}
+
+pub struct PlacementPlugin;
+
+impl Plugin for PlacementPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PlacementPreview>()
+            .init_resource::<Placement>()
+            .add_system(load_placement_assets.in_schedule(OnEnter(GameState::GamePlay)))
+            .add_systems(
+                (
+                    choose_placement,
+                    update_placement_preview.after(choose_placement),
+                    // Build buttons may spend the money the preview was checked against
+                    place_tower
+                        .after(update_placement_preview)
+                        .after(tower_button_clicked)
+                        .run_if(pointer_off_ui),
+                )
+                    .in_set(FrameSet::Input)
+                    .in_set(OnUpdate(GameState::GamePlay)),
+            );
+    }
+}
+
+fn load_placement_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(PlacementAssets {
+        preview_mesh: meshes.add(shape::Capsule::default().into()),
+        valid_material: materials.add(Color::rgba(0.3, 0.9, 0.3, 0.5).into()),
+        invalid_material: materials.add(Color::rgba(0.9, 0.3, 0.3, 0.5).into()),
+        collider_material: materials.add(Color::rgba(0.3, 0.5, 0.3, 0.3).into()),
+        selected_collider_material: materials.add(Color::rgba(0.3, 0.9, 0.3, 0.9).into()),
+    });
+}
+
+/// Whether a tower centred on `position` would be inside the map, off the path and
+/// clear of everything in `occupied`
+pub fn placement_is_valid(
+    position: Vec2,
+    path: &TargetPath,
+    mut occupied: impl Iterator<Item = Vec2>,
+) -> bool {
+    let inside_map = position.x.abs() <= MAP_HALF_EXTENT - TOWER_RADIUS
+        && position.y.abs() <= MAP_HALF_EXTENT - TOWER_RADIUS;
+
+    inside_map
+        && path.distance_to(position) > PATH_CLEARANCE + TOWER_RADIUS
+        && occupied.all(|other| other.distance(position) > TOWER_RADIUS * 2.0)
+}
+
+fn choose_placement(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    mut placement: ResMut<Placement>,
+    previews: Query<Entity, With<PlacementPreview>>,
+    assets: Res<PlacementAssets>,
+) {
+    let chosen = if keyboard.just_pressed(KeyCode::Key1) {
+        Some(TowerType::Tomato)
+    } else if keyboard.just_pressed(KeyCode::Key2) {
+        Some(TowerType::Potato)
+    } else if keyboard.just_pressed(KeyCode::Key3) {
+        Some(TowerType::Cabbage)
+    } else if keyboard.just_pressed(KeyCode::Key4) {
+        Some(TowerType::Mortar)
+    } else if keyboard.just_pressed(KeyCode::Key5) {
+        Some(TowerType::Laser)
+    } else {
+        None
+    };
+
+    if let Some(tower_type) = chosen {
+        placement.tower_type = Some(tower_type);
+
+        if previews.is_empty() {
+            commands
+                .spawn(PbrBundle {
+                    mesh: assets.preview_mesh.clone(),
+                    material: assets.invalid_material.clone(),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                })
+                .insert(PlacementPreview::default())
+                .insert(GamePlayEntity)
+                .insert(NotShadowCaster)
+                .insert(Name::new("Placement Preview"));
+        }
+    } else if mouse.just_pressed(MouseButton::Right) {
+        placement.tower_type = None;
+
+        for preview in &previews {
+            commands.entity(preview).despawn_recursive();
+        }
+    }
+}
+
+fn update_placement_preview(
+    placement: Res<Placement>,
+    cursor: GroundCursor,
+    mut previews: Query<(
+        &mut PlacementPreview,
+        &mut Transform,
+        &mut Visibility,
+        &mut Handle<StandardMaterial>,
+    )>,
+    rules: PlacementRules,
+    player: Query<&Player>,
+    assets: Res<PlacementAssets>,
+) {
+    let Some(tower_type) = placement.tower_type else {
+        return;
+    };
+    let player = player.single();
+    let ground_point = cursor.ground_point();
+
+    for (mut preview, mut transform, mut visibility, mut material) in &mut previews {
+        match ground_point {
+            Some(point) => {
+                let position = Vec3::new(point.x, TOWER_HEIGHT, point.z);
+                let valid = player.money >= tower_type.cost() && rules.allows(position);
+
+                transform.translation = position;
+                *visibility = Visibility::Visible;
+                *material = if valid {
+                    assets.valid_material.clone()
+                } else {
+                    assets.invalid_material.clone()
+                };
+
+                preview.position = Some(transform.translation);
+                preview.valid = valid;
+            }
+            None => {
+                *visibility = Visibility::Hidden;
+                preview.position = None;
+                preview.valid = false;
+            }
+        }
+    }
+}
+
+/// Clicks on buttons are not meant for the ground underneath
+fn pointer_off_ui(interactions: Query<&Interaction>) -> bool {
+    interactions
+        .iter()
+        .all(|interaction| matches!(interaction, Interaction::None))
+}
+
+fn place_tower(
+    mut commands: Commands,
+    mouse: Res<Input<MouseButton>>,
+    mut placement: ResMut<Placement>,
+    previews: Query<(Entity, &PlacementPreview)>,
+    mut player: Query<&mut Player>,
+    game_assets: Res<GameAssets>,
+    assets: Res<PlacementAssets>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(tower_type) = placement.tower_type else {
+        return;
+    };
+    let mut player = player.single_mut();
+
+    for (entity, preview) in &previews {
+        let Some(position) = preview.position else {
+            continue;
+        };
+        if !preview.valid || player.money < tower_type.cost() {
+            continue;
+        }
+
+        player.money -= tower_type.cost();
+
+        let tower = spawn_tower(&mut commands, &game_assets, position, tower_type);
+        commands
+            .entity(tower)
+            .insert(assets.preview_mesh.clone())
+            .insert(Highlighting {
+                initial: assets.collider_material.clone(),
+                hovered: Some(assets.selected_collider_material.clone()),
+                pressed: Some(assets.selected_collider_material.clone()),
+                selected: Some(assets.selected_collider_material.clone()),
+            })
+            .insert(assets.collider_material.clone())
+            .insert(NotShadowCaster)
+            .insert(PickableBundle::default());
+
+        commands.entity(entity).despawn_recursive();
+        placement.tower_type = None;
+    }
+}
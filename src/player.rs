@@ -1,5 +1,9 @@
 use crate::*;
 
+pub const STARTING_HEALTH: u32 = 10;
+
+pub const STARTING_MONEY: u32 = 100;
+
 #[derive(Component, Reflect, Default)]
 pub struct Player {
     pub money: u32,
@@ -11,11 +15,29 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Player>()
-            .add_systems(
-                (spawn_player, spawn_gameplay_ui).in_schedule(OnEnter(GameState::GamePlay)),
+            .add_system(spawn_player.in_schedule(OnEnter(GameState::GamePlay)))
+            .add_system(
+                hurt_player
+                    .in_set(SimulationSet::Resolve)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
+            .add_system(
+                give_money_on_kill
+                    .in_set(SimulationSet::Economy)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
+
+/// The health and money HUD and the sound of a target leaking
+pub struct PlayerViewPlugin;
+
+impl Plugin for PlayerViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(spawn_gameplay_ui.in_schedule(OnEnter(GameState::GamePlay)))
             .add_systems(
-                (give_money_on_kill, hurt_player, update_player_ui)
+                (update_player_ui, play_leak_sound)
+                    .in_set(FrameSet::Ui)
                     .in_set(OnUpdate(GameState::GamePlay)),
             );
     }
@@ -24,9 +46,10 @@ impl Plugin for PlayerPlugin {
 fn spawn_player(mut commands: Commands) {
     commands.spawn((
         Player {
-            money: 100,
-            health: 10,
+            money: STARTING_MONEY,
+            health: STARTING_HEALTH,
         },
+        GamePlayEntity,
         Name::new("Player"),
     ));
 }
@@ -34,10 +57,13 @@ fn spawn_player(mut commands: Commands) {
 fn give_money_on_kill(
     mut player: Query<&mut Player>,
     mut death_events: EventReader<TargetDeathEvent>,
+    mut results: ResMut<GameResults>,
 ) {
     let mut player = player.single_mut();
     for _event in death_events.iter() {
         player.money += 10;
+        results.kills += 1;
+        results.money_earned += 10;
     }
 }
 
@@ -46,14 +72,13 @@ fn hurt_player(
     targets: Query<(Entity, &Target)>,
     path: Res<TargetPath>,
     mut player: Query<&mut Player>,
-    audio: Res<Audio>,
-    asset_server: Res<AssetServer>,
+    mut leaks: EventWriter<TargetLeakEvent>,
+    mut game_state: ResMut<NextState<GameState>>,
 ) {
     for (entity, target) in &targets {
-        if target.path_index >= path.waypoints.len() {
+        if target.leaked(&path) {
             commands.entity(entity).despawn_recursive();
-
-            audio.play(asset_server.load("damage.wav"));
+            leaks.send(TargetLeakEvent { target: entity });
 
             let mut player = player.single_mut();
             if player.health > 0 {
@@ -61,12 +86,26 @@ fn hurt_player(
             }
 
             if player.health == 0 {
-                info!("GAME OVER!");
+                game_state.set(GameState::GameOver);
             }
         }
     }
 }
 
+fn play_leak_sound(
+    mut leaks: EventReader<TargetLeakEvent>,
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+    settings: Res<GameSettings>,
+) {
+    for _event in leaks.iter() {
+        audio.play_with_settings(
+            asset_server.load("damage.wav"),
+            PlaybackSettings::ONCE.with_volume(settings.volume),
+        );
+    }
+}
+
 #[derive(Component, Reflect)]
 pub struct GamePlayUIRoot;
 
@@ -89,6 +128,7 @@ fn spawn_gameplay_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         })
         .insert(GamePlayUIRoot)
+        .insert(GamePlayEntity)
         .with_children(|commands| {
             commands.spawn(NodeBundle {
                 style: Style {
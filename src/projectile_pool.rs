@@ -0,0 +1,118 @@
+use crate::*;
+use bevy::utils::{HashMap, HashSet};
+use bevy_rapier3d::prelude::*;
+
+/// Marks a bullet entity as owned by the `ProjectilePool`. It stays on the entity while
+/// it sits idle in the pool
+#[derive(Component)]
+pub struct PooledProjectile {
+    pub tower_type: TowerType,
+}
+
+/// Sent instead of despawning a bullet so its entity can be recycled
+pub struct ReleaseBulletEvent {
+    pub bullet: Entity,
+}
+
+/// Hidden bullet entities waiting to be fired again, one free list per `TowerType`
+#[derive(Resource, Default)]
+pub struct ProjectilePool {
+    free: HashMap<TowerType, Vec<Entity>>,
+}
+
+#[derive(Reflect, Default, Clone, Copy, Debug)]
+pub struct PoolStats {
+    /// Bullet entities spawned because the pool was empty
+    pub created: u32,
+    /// Shots that reused an idle entity
+    pub reused: u32,
+    pub active: u32,
+    pub idle: u32,
+}
+
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct ProjectilePoolStats {
+    pub tomato: PoolStats,
+    pub potato: PoolStats,
+    pub cabbage: PoolStats,
+    pub mortar: PoolStats,
+    pub laser: PoolStats,
+}
+
+impl ProjectilePoolStats {
+    pub fn get_mut(&mut self, tower_type: TowerType) -> &mut PoolStats {
+        use TowerType::*;
+        match tower_type {
+            Tomato => &mut self.tomato,
+            Potato => &mut self.potato,
+            Cabbage => &mut self.cabbage,
+            Mortar => &mut self.mortar,
+            Laser => &mut self.laser,
+        }
+    }
+}
+
+impl ProjectilePool {
+    /// Takes an idle bullet of `tower_type` out of the pool, skipping any entity that
+    /// has been despawned since it was released
+    pub fn acquire(
+        &mut self,
+        commands: &mut Commands,
+        stats: &mut ProjectilePoolStats,
+        tower_type: TowerType,
+    ) -> Option<Entity> {
+        let free = self.free.entry(tower_type).or_default();
+        let stats = stats.get_mut(tower_type);
+
+        while let Some(bullet) = free.pop() {
+            stats.idle = free.len() as u32;
+            if commands.get_entity(bullet).is_some() {
+                stats.reused += 1;
+                stats.active += 1;
+                return Some(bullet);
+            }
+        }
+
+        stats.created += 1;
+        stats.active += 1;
+        None
+    }
+
+    fn release(&mut self, stats: &mut ProjectilePoolStats, tower_type: TowerType, bullet: Entity) {
+        let free = self.free.entry(tower_type).or_default();
+        free.push(bullet);
+
+        let stats = stats.get_mut(tower_type);
+        stats.active = stats.active.saturating_sub(1);
+        stats.idle = free.len() as u32;
+    }
+}
+
+/// Hides released bullets and returns them to the pool. Several systems may release the
+/// same bullet in one frame, so each entity is only recycled once
+pub fn recycle_bullets(
+    mut commands: Commands,
+    mut events: EventReader<ReleaseBulletEvent>,
+    bullets: Query<&PooledProjectile, With<Bullet>>,
+    mut pool: ResMut<ProjectilePool>,
+    mut stats: ResMut<ProjectilePoolStats>,
+) {
+    let mut released = HashSet::new();
+
+    for event in events.iter() {
+        if !released.insert(event.bullet) {
+            continue;
+        }
+        let Ok(pooled) = bullets.get(event.bullet) else {
+            continue;
+        };
+
+        commands
+            .entity(event.bullet)
+            .remove::<(Bullet, Ballistic, Lifetime, Collider)>()
+            .insert(Visibility::Hidden);
+
+        pool.release(&mut stats, pooled.tower_type, event.bullet);
+    }
+}
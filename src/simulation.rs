@@ -0,0 +1,171 @@
+use crate::*;
+
+/// Simulation ticks per second of game time
+pub const SIMULATION_HZ: f32 = 60.0;
+
+/// Stages of one gameplay tick in `CoreSchedule::FixedUpdate`, run in this order so a run
+/// only depends on its inputs and not on the frame rate or system scheduling. Commands are
+/// applied after `Spawn`, `Hit` and `Resolve`, so each stage sees the entities the previous
+/// one spawned or removed
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimulationSet {
+    /// Puts back the simulated transforms that rendering interpolated
+    Restore,
+    /// New waves of targets enter the map
+    Spawn,
+    /// Targets walk the path and bullets fly, then the target grid is rebuilt
+    Move,
+    /// Towers fire and bullets and beams damage targets
+    Hit,
+    /// Dead and leaked targets are removed and spent bullets recycled
+    Resolve,
+    /// Rewards, stats and the win and lose checks read what this tick resolved
+    Economy,
+    /// Saves the simulated transforms for `interpolate_transforms`
+    Record,
+}
+
+/// Steps of `SimulationSet::Hit`, run in this order. When several could land the killing
+/// blow on a target in the same tick, the earliest one gets the kill
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HitSet {
+    /// Towers fire, their bullets first move next tick
+    Fire,
+    /// Beams damage their locked targets
+    Beam,
+    /// Bullets that reached a target hit it
+    Impact,
+    /// Bullets past their range are released
+    Expire,
+    /// Shells that reached the ground explode
+    Blast,
+}
+
+/// Stages of gameplay in `Update`, which runs after the frame's fixed ticks. Player input
+/// is handled before the HUD shows the result
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameSet {
+    Input,
+    Ui,
+}
+
+/// The last two simulated transforms of an entity moved by the fixed tick. Its
+/// `Transform` is only rendered between them, and put back to `current` before the next
+/// tick reads it
+#[derive(Component, Reflect)]
+pub struct Interpolated {
+    pub previous: Transform,
+    pub current: Transform,
+}
+
+impl Interpolated {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            previous: transform,
+            current: transform,
+        }
+    }
+}
+
+/// Whether the tick being run may play, decided once before it starts so a tick that ends
+/// the game still resolves and pays out everything that happened in it
+#[derive(Resource, Default)]
+pub struct TickAllowed(pub bool);
+
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<PauseState>()
+            .register_type::<Interpolated>()
+            .init_resource::<TickAllowed>()
+            .insert_resource(FixedTime::new_from_secs(1.0 / SIMULATION_HZ))
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                use SimulationSet::*;
+                let stages = [Restore, Spawn, Move, Hit, Resolve, Economy, Record];
+
+                schedule.add_system(begin_tick.before(Restore));
+                for set in stages {
+                    schedule.configure_set(
+                        set.run_if(in_state(GameState::GamePlay))
+                            .run_if(in_state(PauseState::Running))
+                            .run_if(tick_allowed),
+                    );
+                }
+                for pair in stages.windows(2) {
+                    schedule.configure_set(pair[0].before(pair[1]));
+                }
+                for (stage, next) in [(Spawn, Move), (Hit, Resolve), (Resolve, Economy)] {
+                    schedule.add_system(apply_system_buffers.after(stage).before(next));
+                }
+
+                let hit_steps = [
+                    HitSet::Fire,
+                    HitSet::Beam,
+                    HitSet::Impact,
+                    HitSet::Expire,
+                    HitSet::Blast,
+                ];
+                for step in hit_steps {
+                    schedule.configure_set(step.in_set(Hit));
+                }
+                for pair in hit_steps.windows(2) {
+                    schedule.configure_set(pair[0].before(pair[1]));
+                }
+            })
+            .configure_set(FrameSet::Input.before(FrameSet::Ui))
+            .add_system(
+                restore_simulated_transforms
+                    .in_set(SimulationSet::Restore)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                record_simulated_transforms
+                    .in_set(SimulationSet::Record)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(interpolate_transforms.in_set(OnUpdate(GameState::GamePlay)));
+    }
+}
+
+/// Once a tick has won, lost or restarted the game, the frame's remaining ticks must not
+/// play on before the state changes
+fn begin_tick(next_state: Res<NextState<GameState>>, mut allowed: ResMut<TickAllowed>) {
+    allowed.0 = next_state.0.is_none();
+}
+
+fn tick_allowed(allowed: Res<TickAllowed>) -> bool {
+    allowed.0
+}
+
+fn restore_simulated_transforms(mut entities: Query<(&mut Transform, &Interpolated)>) {
+    for (mut transform, interpolated) in &mut entities {
+        *transform = interpolated.current;
+    }
+}
+
+fn record_simulated_transforms(mut entities: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in &mut entities {
+        interpolated.previous = interpolated.current;
+        interpolated.current = *transform;
+    }
+}
+
+/// Renders each entity part way between its last two ticks, by how far the fixed clock
+/// has run towards the next one
+pub fn interpolate_transforms(
+    mut entities: Query<(&mut Transform, &Interpolated)>,
+    fixed_time: Res<FixedTime>,
+) {
+    let alpha =
+        (fixed_time.accumulated().as_secs_f32() / fixed_time.period.as_secs_f32()).clamp(0.0, 1.0);
+
+    for (mut transform, interpolated) in &mut entities {
+        let (previous, current) = (interpolated.previous, interpolated.current);
+        *transform = Transform {
+            translation: previous.translation.lerp(current.translation, alpha),
+            rotation: previous.rotation.slerp(current.rotation, alpha),
+            scale: current.scale,
+        };
+    }
+}
@@ -0,0 +1,63 @@
+use crate::*;
+use bevy::{math::Vec3Swizzles, utils::HashMap};
+
+/// Uniform grid over the ground plane bucketing every `Target` by position, so range
+/// queries only look at nearby targets instead of all of them
+#[derive(Resource)]
+pub struct TargetGrid {
+    pub cell_size: f32,
+    cells: HashMap<IVec2, Vec<(Entity, Vec3)>>,
+}
+
+impl Default for TargetGrid {
+    fn default() -> Self {
+        Self {
+            cell_size: 2.0,
+            cells: HashMap::default(),
+        }
+    }
+}
+
+impl TargetGrid {
+    fn cell(&self, position: Vec3) -> IVec2 {
+        (position.xz() / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Empties every cell while keeping their allocations for the next rebuild
+    pub fn clear(&mut self) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec3) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push((entity, position));
+    }
+
+    /// Every target strictly closer than `radius` to `center`
+    pub fn within(&self, center: Vec3, radius: f32) -> impl Iterator<Item = (Entity, Vec3)> + '_ {
+        let min = self.cell(center - Vec3::splat(radius));
+        let max = self.cell(center + Vec3::splat(radius));
+
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(move |(_, position)| position.distance(center) < radius)
+    }
+}
+
+pub fn rebuild_target_grid(
+    mut grid: ResMut<TargetGrid>,
+    targets: Query<(Entity, &Target, &Transform)>,
+    path: Res<TargetPath>,
+) {
+    grid.clear();
+    for (entity, target, transform) in &targets {
+        if !target.leaked(&path) {
+            grid.insert(entity, transform.translation);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::*;
 use bevy::math::Vec3Swizzles;
+use bevy_rapier3d::prelude::*;
 
 #[derive(Component, Reflect, Default)]
 pub struct Target {
@@ -7,18 +8,72 @@ pub struct Target {
     pub path_index: usize,
 }
 
+impl Target {
+    /// Whether this target has walked past the last waypoint. Leaked targets can no
+    /// longer be hit and are removed when the tick resolves
+    pub fn leaked(&self, path: &TargetPath) -> bool {
+        self.path_index >= path.waypoints.len()
+    }
+
+    /// Velocity of a target at `position` heading for its next waypoint on `path`
+    pub fn velocity(&self, position: Vec3, path: &TargetPath) -> Vec3 {
+        path.waypoints
+            .get(self.path_index)
+            .map_or(Vec3::ZERO, |waypoint| {
+                (*waypoint - position.xz())
+                    .normalize_or_zero()
+                    .extend(0.0)
+                    .xzy()
+                    * self.speed
+            })
+    }
+}
+
 #[derive(Resource)]
 pub struct TargetPath {
+    /// Where targets enter the map, before heading to the first waypoint
+    pub start: Vec2,
     pub waypoints: Vec<Vec2>,
 }
 
+impl TargetPath {
+    /// Shortest distance from `point` to any leg of the path
+    pub fn distance_to(&self, point: Vec2) -> f32 {
+        std::iter::once(self.start)
+            .chain(self.waypoints.iter().copied())
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|leg| {
+                let (a, b) = (leg[0], leg[1]);
+                let t = ((point - a).dot(b - a) / (b - a).length_squared()).clamp(0.0, 1.0);
+                point.distance(a + (b - a) * t)
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+}
+
 #[derive(Component, Reflect)]
 pub struct Health {
     pub value: i32,
 }
 
-// Can have attached data
-pub struct TargetDeathEvent;
+/// The tower whose bullet most recently hit this target
+#[derive(Component, Reflect, Default)]
+pub struct LastHitBy {
+    pub tower: Option<Entity>,
+}
+
+pub struct TargetDeathEvent {
+    pub target: Entity,
+    pub position: Vec3,
+    /// The tower that landed the killing blow, if it is known
+    pub killer: Option<Entity>,
+}
+
+/// Sent when a target walks off the end of the path and costs the player health
+pub struct TargetLeakEvent {
+    pub target: Entity,
+}
 
 pub struct TargetPlugin;
 
@@ -26,26 +81,67 @@ impl Plugin for TargetPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Target>()
             .register_type::<Health>()
+            .register_type::<LastHitBy>()
             .add_event::<TargetDeathEvent>()
+            .add_event::<TargetLeakEvent>()
             .insert_resource(TargetPath {
+                start: Vec2::new(-10.0, 1.5),
                 waypoints: vec![
                     Vec2::new(6.0, 2.0),
                     Vec2::new(6.0, 6.0),
                     Vec2::new(9.0, 9.0),
                 ],
             })
-            .add_systems((move_targets, target_death).in_set(OnUpdate(GameState::GamePlay)));
+            .init_resource::<TargetGrid>()
+            .add_systems(
+                (move_targets, rebuild_target_grid.after(move_targets))
+                    .in_set(SimulationSet::Move)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                target_death
+                    .in_set(SimulationSet::Resolve)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
     }
 }
 
+pub fn spawn_target(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    position: Vec3,
+    health: i32,
+    speed: f32,
+) -> Entity {
+    commands
+        .spawn(SceneBundle {
+            scene: assets.target_scene.clone(),
+            transform: Transform::from_translation(position),
+            ..default()
+        })
+        .insert(Target { speed, ..default() })
+        .insert(Health { value: health })
+        .insert(LastHitBy::default())
+        .insert(Collider::capsule_y(0.2, 0.15))
+        .insert(Sensor)
+        .insert(Interpolated::new(Transform::from_translation(position)))
+        .insert(GamePlayEntity)
+        .insert(Name::new("Target"))
+        .id()
+}
+
 fn target_death(
     mut commands: Commands,
-    targets: Query<(Entity, &Health)>,
+    targets: Query<(Entity, &Health, &Transform, Option<&LastHitBy>)>,
     mut death_event_writer: EventWriter<TargetDeathEvent>,
 ) {
-    for (entity, health) in &targets {
+    for (entity, health, transform, last_hit) in &targets {
         if health.value <= 0 {
-            death_event_writer.send(TargetDeathEvent);
+            death_event_writer.send(TargetDeathEvent {
+                target: entity,
+                position: transform.translation,
+                killer: last_hit.and_then(|last_hit| last_hit.tower),
+            });
             commands.entity(entity).despawn_recursive();
         }
     }
@@ -54,22 +150,27 @@ fn target_death(
 fn move_targets(
     mut targets: Query<(&mut Target, &mut Transform)>,
     path: Res<TargetPath>,
-    time: Res<Time>,
+    time: Res<FixedTime>,
 ) {
     // for (target, mut transform) in &mut targets {
     //     transform.translation.x += target.speed * time.delta_seconds();
     // }
 
     for (mut target, mut transform) in &mut targets {
-        let delta = target.speed * time.delta_seconds();
-        let delta_target = path.waypoints[target.path_index] - transform.translation.xz();
+        // Targets past the last waypoint have leaked and are about to be removed
+        let Some(&waypoint) = path.waypoints.get(target.path_index) else {
+            continue;
+        };
+
+        let delta = target.speed * time.period.as_secs_f32();
+        let delta_target = waypoint - transform.translation.xz();
 
         if delta_target.length() > delta {
             let movement = delta_target.normalize() * delta;
             transform.translation += movement.extend(0.0).xzy();
 
             let y = transform.translation.y;
-            transform.look_at(path.waypoints[target.path_index].extend(y).xzy(), Vec3::Y);
+            transform.look_at(waypoint.extend(y).xzy(), Vec3::Y);
         } else {
             target.path_index += 1;
         }
@@ -1,11 +1,89 @@
 use crate::*;
-use bevy::utils::FloatOrd;
+use bevy::{ecs::system::SystemParam, utils::FloatOrd};
+use bevy_mod_picking::Selection;
 
 #[derive(Component, Reflect)]
 pub struct Tower {
     pub shooting_timer: Timer,
     pub bullet_offset: Vec3,
+    /// Speed of the bullets it fires, horizontal speed for `AttackMode::Artillery`
+    pub bullet_speed: f32,
     pub range: f32,
+    pub attack: AttackMode,
+}
+
+#[derive(Reflect, Clone, Copy, Debug)]
+pub enum AttackMode {
+    /// Fires a `Bullet` every time the shooting timer finishes
+    Projectile,
+    /// Lobs a `Ballistic` bullet onto where the target will be, exploding on
+    /// the ground. Targets closer than `min_range` are out of reach
+    Artillery { min_range: f32, blast_radius: f32 },
+    /// Locks onto one target and damages it continuously, ramping up the
+    /// longer the lock is held
+    Beam {
+        damage_per_second: f32,
+        ramp_per_second: f32,
+        max_ramp: f32,
+    },
+}
+
+/// What towers need to find targets they can shoot at
+#[derive(SystemParam)]
+pub struct Targeting<'w, 's> {
+    targets: Query<'w, 's, &'static Target>,
+    grid: Res<'w, TargetGrid>,
+    path: Res<'w, TargetPath>,
+    rapier: Res<'w, RapierContext>,
+}
+
+impl Targeting<'_, '_> {
+    /// Targets `tower` can shoot at from `bullet_spawn`: in range, outside the dead zone
+    /// of artillery and in line of sight
+    pub fn candidates(
+        &self,
+        tower: &Tower,
+        bullet_spawn: Vec3,
+    ) -> impl Iterator<Item = (Entity, Vec3)> + '_ {
+        let min_range = match tower.attack {
+            AttackMode::Artillery { min_range, .. } => min_range,
+            _ => 0.0,
+        };
+        let rapier: &RapierContext = &self.rapier;
+
+        self.grid
+            .within(bullet_spawn, tower.range)
+            .filter(move |(_, position)| position.distance(bullet_spawn) >= min_range)
+            .filter(move |(_, position)| line_of_sight(rapier, bullet_spawn, *position))
+    }
+
+    /// Where a target at `position` is heading, `None` if it is no longer a target
+    pub fn velocity(&self, target: Entity, position: Vec3) -> Option<Vec3> {
+        let target = self.targets.get(target).ok()?;
+        Some(target.velocity(position, &self.path))
+    }
+}
+
+/// Running combat totals for a single tower
+#[derive(Component, Reflect, Default, Clone, Debug)]
+pub struct TowerStats {
+    pub shots_fired: u32,
+    pub hits: u32,
+    pub damage_dealt: u32,
+    pub kills: u32,
+    /// Damage beyond what targets had left, wasted on the killing blow or on targets
+    /// that were already dead
+    pub overkill: u32,
+}
+
+impl TowerStats {
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.hits as f32 / self.shots_fired as f32
+        }
+    }
 }
 
 pub struct TowerPlugin;
@@ -15,16 +93,41 @@ impl Plugin for TowerPlugin {
         // app.register_type::<Tower>().add_system(tower_shooting)
         // // .add_system(build_tower);
         app.register_type::<Tower>()
+            .register_type::<TowerStats>()
             // .register_inspectable::<TowerType>()
-            .add_systems(
-                (
-                    tower_shooting,
-                    tower_button_clicked,
-                    create_ui_on_selection,
-                    grey_tower_buttons.after(create_ui_on_selection),
-                )
-                    .in_set(OnUpdate(GameState::GamePlay)),
-            );
+            .add_system(
+                tower_shooting
+                    .in_set(HitSet::Fire)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                record_hit_stats
+                    .in_set(SimulationSet::Economy)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(record_tower_stats.in_schedule(OnExit(GameState::GamePlay)));
+    }
+}
+
+/// The build buttons on tower bases and the selected tower's stats panel
+pub struct TowerViewPlugin;
+
+impl Plugin for TowerViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (tower_button_clicked, create_ui_on_selection)
+                .in_set(FrameSet::Input)
+                .in_set(OnUpdate(GameState::GamePlay)),
+        )
+        .add_systems(
+            (
+                grey_tower_buttons,
+                create_stats_ui_on_selection,
+                update_stats_ui.after(create_stats_ui_on_selection),
+            )
+                .in_set(FrameSet::Ui)
+                .in_set(OnUpdate(GameState::GamePlay)),
+        );
     }
 }
 
@@ -33,53 +136,143 @@ fn tower_shooting(
     // mut meshes: ResMut<Assets<Mesh>>,
     // mut materials: ResMut<Assets<StandardMaterial>>,
     bullet_assets: Res<GameAssets>,
-    mut towers: Query<(Entity, &mut Tower, &TowerType, &GlobalTransform)>,
-    targets: Query<&GlobalTransform, With<Target>>,
-    time: Res<Time>,
+    mut pool: ResMut<ProjectilePool>,
+    mut pool_stats: ResMut<ProjectilePoolStats>,
+    mut towers: Query<(Entity, &mut Tower, &mut TowerStats, &TowerType, &Transform)>,
+    targeting: Targeting,
+    time: Res<FixedTime>,
 ) {
-    for (tower_entity, mut tower, tower_type, transform) in &mut towers {
-        tower.shooting_timer.tick(time.delta());
+    for (tower_entity, mut tower, mut stats, tower_type, transform) in &mut towers {
+        if let AttackMode::Beam { .. } = tower.attack {
+            continue;
+        }
+
+        tower.shooting_timer.tick(time.period);
 
         if tower.shooting_timer.just_finished() {
-            let bullet_spawn = transform.translation() + tower.bullet_offset;
+            // Towers are root entities, so `Transform` is in world space and, unlike
+            // `GlobalTransform`, already correct on the tick a tower is built
+            let bullet_spawn = transform.translation + tower.bullet_offset;
 
-            let direction = targets
-                .iter()
-                .filter(|target_transform| {
-                    Vec3::distance(target_transform.translation(), bullet_spawn) < tower.range
-                })
-                .min_by_key(|target_transform| {
-                    FloatOrd(Vec3::distance(target_transform.translation(), bullet_spawn))
-                })
-                .map(|closest_target| closest_target.translation() - bullet_spawn);
+            let closest_target =
+                targeting
+                    .candidates(&tower, bullet_spawn)
+                    .min_by_key(|(_, target_position)| {
+                        FloatOrd(Vec3::distance(*target_position, bullet_spawn))
+                    });
 
-            if let Some(direction) = direction {
-                let (model, bullet) = tower_type.get_bullet(direction, &bullet_assets);
-                commands.entity(tower_entity).with_children(|commands| {
-                    commands
-                        // .spawn(PbrBundle {
-                        //     mesh: meshes.add(Mesh::from(shape::Cube { size: 0.1 })),
-                        //     material: materials.add(Color::rgb(0.87, 0.44, 0.42).into()),
-                        //     transform: Transform::from_xyz(0.0, 0.7, 0.6),
-                        //     // .with_rotation(Quat::from_rotation_y(-PI / 2.0)),
-                        //     ..default()
-                        // })
-                        .spawn(SceneBundle {
-                            scene: model,
-                            transform: Transform::from_translation(tower.bullet_offset),
-                            ..default()
-                        })
-                        .insert(Lifetime {
-                            timer: Timer::from_seconds(2.0, TimerMode::Once),
-                        })
-                        .insert(bullet)
-                        .insert(Name::new("Bullet"));
-                });
+            if let Some((target, target_position)) = closest_target {
+                let Some(target_velocity) = targeting.velocity(target, target_position) else {
+                    continue;
+                };
+
+                stats.shots_fired += 1;
+                fire_bullet(
+                    &mut commands,
+                    &bullet_assets,
+                    &mut pool,
+                    &mut pool_stats,
+                    tower_entity,
+                    &tower,
+                    *tower_type,
+                    transform.translation,
+                    target_position,
+                    target_velocity,
+                );
             }
         }
     }
 }
 
+/// Fires a bullet from `tower`, standing at `tower_position`, at a target currently at
+/// `target_position`. Artillery leads the target by `target_velocity`, other bullets fly
+/// straight at it. The bullet entity is taken from `pool` when one is idle and lives in
+/// world space, so it outlives the tower that fired it
+#[allow(clippy::too_many_arguments)]
+pub fn fire_bullet(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    pool: &mut ProjectilePool,
+    pool_stats: &mut ProjectilePoolStats,
+    tower_entity: Entity,
+    tower: &Tower,
+    tower_type: TowerType,
+    tower_position: Vec3,
+    target_position: Vec3,
+    target_velocity: Vec3,
+) {
+    let bullet_spawn = tower_position + tower.bullet_offset;
+    let (model, mut bullet) = tower_type.get_bullet(
+        target_position - bullet_spawn,
+        tower.bullet_speed,
+        tower_entity,
+        assets,
+    );
+
+    let ballistic = match tower.attack {
+        AttackMode::Artillery { blast_radius, .. } => {
+            let velocity = ballistic_launch_velocity(
+                bullet_spawn,
+                target_position,
+                target_velocity,
+                bullet.speed,
+            );
+            bullet.direction = velocity;
+            Some(Ballistic {
+                velocity,
+                blast_radius,
+            })
+        }
+        _ => None,
+    };
+
+    let bullet_entity = match pool.acquire(commands, pool_stats, tower_type) {
+        Some(reused) => {
+            commands
+                .entity(reused)
+                .insert(Transform::from_translation(bullet_spawn))
+                .insert(Interpolated::new(Transform::from_translation(bullet_spawn)))
+                .insert(Visibility::Inherited);
+            reused
+        }
+        None => commands
+            // .spawn(PbrBundle {
+            //     mesh: meshes.add(Mesh::from(shape::Cube { size: 0.1 })),
+            //     material: materials.add(Color::rgb(0.87, 0.44, 0.42).into()),
+            //     transform: Transform::from_xyz(0.0, 0.7, 0.6),
+            //     // .with_rotation(Quat::from_rotation_y(-PI / 2.0)),
+            //     ..default()
+            // })
+            .spawn(SceneBundle {
+                scene: model,
+                transform: Transform::from_translation(bullet_spawn),
+                ..default()
+            })
+            .insert(Sensor)
+            .insert(ActiveEvents::COLLISION_EVENTS)
+            .insert(ActiveCollisionTypes::all())
+            .insert(PooledProjectile { tower_type })
+            .insert(Interpolated::new(Transform::from_translation(bullet_spawn)))
+            .insert(GamePlayEntity)
+            .insert(Name::new("Bullet"))
+            .id(),
+    };
+
+    let mut bullet_commands = commands.entity(bullet_entity);
+    bullet_commands
+        .insert(Lifetime {
+            origin: bullet_spawn,
+            max_distance: tower.range + RANGE_MARGIN,
+        })
+        .insert(bullet)
+        .insert(PreviousPosition(bullet_spawn))
+        .insert(tower_type.bullet_collider());
+
+    if let Some(ballistic) = ballistic {
+        bullet_commands.insert(ballistic);
+    }
+}
+
 pub fn spawn_tower(
     commands: &mut Commands,
     assets: &GameAssets,
@@ -87,21 +280,32 @@ pub fn spawn_tower(
     tower_type: TowerType,
 ) -> Entity {
     let (tower_scene, tower) = tower_type.get_tower(assets);
-    commands
-        .spawn(SpatialBundle::from_transform(Transform::from_translation(
-            position,
-        )))
+    let attack = tower.attack;
+
+    let mut entity = commands.spawn(SpatialBundle::from_transform(Transform::from_translation(
+        position,
+    )));
+    entity
         .insert(Name::new(format!("{tower_type:?} Tower")))
         .insert(tower_type)
         .insert(tower)
+        .insert(TowerStats::default())
+        .insert(GamePlayEntity)
+        .insert(Veterancy::default())
+        .insert(tower_type.ability())
         .with_children(|commands| {
             commands.spawn(SceneBundle {
                 scene: tower_scene,
                 transform: Transform::from_xyz(0.0, -0.8, 0.0),
                 ..default()
             });
-        })
-        .id()
+        });
+
+    if let AttackMode::Beam { .. } = attack {
+        entity.insert(Beam::default());
+    }
+
+    entity.id()
 }
 
 #[derive(Component, Reflect, Default)]
@@ -126,3 +330,149 @@ fn grey_tower_buttons(
         }
     }
 }
+
+fn record_hit_stats(mut hits: EventReader<BulletHitEvent>, mut towers: Query<&mut TowerStats>) {
+    for hit in hits.iter() {
+        if let Ok(mut stats) = towers.get_mut(hit.tower) {
+            if !hit.splash {
+                stats.hits += 1;
+            }
+            stats.damage_dealt += hit.damage as u32;
+            stats.overkill += hit.overkill as u32;
+            if hit.killed {
+                stats.kills += 1;
+            }
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct TowerStatsUIRoot;
+
+#[derive(Component, Reflect)]
+pub struct TowerStatsText;
+
+fn create_stats_ui_on_selection(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    selections: Query<&Selection, With<Tower>>,
+    root: Query<Entity, With<TowerStatsUIRoot>>,
+) {
+    let tower_selected = selections.iter().any(|selection| selection.selected());
+
+    match root.get_single() {
+        Ok(root) => {
+            if !tower_selected {
+                commands.entity(root).despawn_recursive();
+            }
+        }
+        Err(bevy::ecs::query::QuerySingleError::NoEntities(..)) => {
+            if tower_selected {
+                spawn_stats_ui(&mut commands, &asset_server);
+            }
+        }
+        _ => unreachable!("Too many UI Tower Stats Roots!"),
+    }
+}
+
+fn spawn_stats_ui(commands: &mut Commands, asset_server: &AssetServer) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Percent(2.0),
+                    top: Val::Percent(12.0),
+                    ..default()
+                },
+                padding: UiRect::all(Val::Percent(1.0)),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: Color::rgba(1.0, 1.0, 1.0, 0.6).into(),
+            ..default()
+        })
+        .insert(TowerStatsUIRoot)
+        .insert(GamePlayEntity)
+        .with_children(|commands| {
+            commands
+                .spawn(TextBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("FiraSans-Bold.ttf"),
+                            font_size: 28.0,
+                            color: Color::BLACK,
+                        },
+                    ),
+                    ..default()
+                })
+                .insert(TowerStatsText);
+
+            commands
+                .spawn(ButtonBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(8.0)),
+                        padding: UiRect::all(Val::Px(6.0)),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(AbilityButton)
+                .with_children(|commands| {
+                    commands
+                        .spawn(TextBundle {
+                            text: Text::from_section(
+                                "",
+                                TextStyle {
+                                    font: asset_server.load("FiraSans-Bold.ttf"),
+                                    font_size: 24.0,
+                                    color: Color::BLACK,
+                                },
+                            ),
+                            ..default()
+                        })
+                        .insert(AbilityButtonText);
+                });
+        });
+}
+
+fn update_stats_ui(
+    towers: Query<(&Selection, &TowerType, &TowerStats, &Veterancy)>,
+    mut stats_ui: Query<&mut Text, With<TowerStatsText>>,
+) {
+    let Ok(mut text) = stats_ui.get_single_mut() else {
+        return;
+    };
+
+    if let Some((_, tower_type, stats, veterancy)) =
+        towers.iter().find(|(selection, ..)| selection.selected())
+    {
+        let experience = match veterancy.next_threshold() {
+            Some(threshold) => format!("{}/{threshold}", veterancy.experience),
+            None => "MAX".to_string(),
+        };
+
+        text.sections[0].value = format!(
+            "{tower_type:?} Tower\nRank: {} ({experience} XP)\nShots: {}\nHits: {} ({:.0}%)\nDamage: {}\nKills: {}",
+            veterancy.rank,
+            stats.shots_fired,
+            stats.hits,
+            stats.accuracy() * 100.0,
+            stats.damage_dealt,
+            stats.kills,
+        );
+    }
+}
+
+/// Keeps each tower's stats for the results screens before the towers are despawned
+fn record_tower_stats(towers: Query<(&Name, &TowerStats)>, mut results: ResMut<GameResults>) {
+    results.towers = towers
+        .iter()
+        .map(|(name, stats)| (name.to_string(), stats.clone()))
+        .collect();
+    results
+        .towers
+        .sort_by(|(_, a), (_, b)| b.damage_dealt.cmp(&a.damage_dealt));
+}
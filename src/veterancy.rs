@@ -0,0 +1,128 @@
+use crate::*;
+
+/// Experience needed to reach each rank, indexed by the current rank
+pub const RANK_THRESHOLDS: [u32; 3] = [3, 8, 15];
+
+/// Experience a tower earns for landing a killing blow
+pub const EXPERIENCE_PER_KILL: u32 = 1;
+
+#[derive(Component, Reflect, Default)]
+pub struct Veterancy {
+    pub experience: u32,
+    pub rank: usize,
+}
+
+impl Veterancy {
+    /// Experience required for the next rank, or `None` at the maximum rank
+    pub fn next_threshold(&self) -> Option<u32> {
+        RANK_THRESHOLDS.get(self.rank).copied()
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct RankBadge;
+
+#[derive(Resource)]
+pub struct VeterancyAssets {
+    pub badge_mesh: Handle<Mesh>,
+    pub badge_material: Handle<StandardMaterial>,
+}
+
+pub struct VeterancyPlugin;
+
+impl Plugin for VeterancyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Veterancy>().add_system(
+            gain_experience
+                .in_set(SimulationSet::Economy)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// Rank badges above veteran towers
+pub struct VeterancyViewPlugin;
+
+impl Plugin for VeterancyViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<RankBadge>()
+            .add_system(load_veterancy_assets.in_schedule(OnEnter(GameState::GamePlay)))
+            .add_system(show_rank_badges.in_set(OnUpdate(GameState::GamePlay)));
+    }
+}
+
+fn load_veterancy_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(VeterancyAssets {
+        badge_mesh: meshes.add(
+            shape::UVSphere {
+                radius: 0.06,
+                ..default()
+            }
+            .into(),
+        ),
+        badge_material: materials.add(Color::GOLD.into()),
+    });
+}
+
+fn gain_experience(
+    mut death_events: EventReader<TargetDeathEvent>,
+    mut towers: Query<(&mut Veterancy, &mut Tower)>,
+) {
+    for event in death_events.iter() {
+        let Some(killer) = event.killer else {
+            continue;
+        };
+        let Ok((mut veterancy, mut tower)) = towers.get_mut(killer) else {
+            continue;
+        };
+
+        veterancy.experience += EXPERIENCE_PER_KILL;
+
+        while let Some(threshold) = veterancy.next_threshold() {
+            if veterancy.experience < threshold {
+                break;
+            }
+            veterancy.rank += 1;
+
+            // Each rank fires 10% faster and reaches a little further
+            let duration = tower.shooting_timer.duration().mul_f32(0.9);
+            tower.shooting_timer.set_duration(duration);
+            tower.range += 0.25;
+        }
+    }
+}
+
+/// Stacks one pip per rank above each tower that has ranked up
+fn show_rank_badges(
+    mut commands: Commands,
+    towers: Query<(Entity, &Veterancy, Option<&Children>), Changed<Veterancy>>,
+    badges: Query<(), With<RankBadge>>,
+    assets: Res<VeterancyAssets>,
+) {
+    for (tower, veterancy, children) in &towers {
+        let shown = children.map_or(0, |children| {
+            children
+                .iter()
+                .filter(|child| badges.contains(**child))
+                .count()
+        });
+
+        commands.entity(tower).with_children(|commands| {
+            for rank in shown + 1..=veterancy.rank {
+                commands
+                    .spawn(PbrBundle {
+                        mesh: assets.badge_mesh.clone(),
+                        material: assets.badge_material.clone(),
+                        transform: Transform::from_xyz(0.0, 0.9 + 0.15 * rank as f32, 0.0),
+                        ..default()
+                    })
+                    .insert(RankBadge)
+                    .insert(Name::new("Rank Badge"));
+            }
+        });
+    }
+}
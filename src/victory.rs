@@ -0,0 +1,140 @@
+use crate::*;
+
+pub struct VictoryPlugin;
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            check_victory
+                .in_set(SimulationSet::Economy)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// The victory screen with the star rating and the run's results
+pub struct VictoryViewPlugin;
+
+impl Plugin for VictoryViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(spawn_victory_ui.in_schedule(OnEnter(GameState::Victory)))
+            .add_system(despawn_with::<VictoryUIRoot>.in_schedule(OnExit(GameState::Victory)))
+            .add_system(continue_button_clicked.in_set(OnUpdate(GameState::Victory)));
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct VictoryUIRoot;
+
+#[derive(Component, Reflect)]
+pub struct ContinueButton;
+
+/// One star for surviving, two for keeping at least half of the starting health and
+/// three for a flawless run
+pub fn star_rating(health: u32) -> u32 {
+    if health >= STARTING_HEALTH {
+        3
+    } else if health * 2 >= STARTING_HEALTH {
+        2
+    } else {
+        1
+    }
+}
+
+fn check_victory(
+    waves: Res<Waves>,
+    targets: Query<(), With<Target>>,
+    player: Query<&Player>,
+    mut results: ResMut<GameResults>,
+    mut levels: ResMut<Levels>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    let player = player.single();
+    if !waves.finished() || !targets.is_empty() || player.health == 0 {
+        return;
+    }
+
+    results.waves_survived = waves.cleared;
+    results.stars = star_rating(player.health);
+    levels.complete_current(results.stars);
+    game_state.set(GameState::Victory);
+}
+
+fn spawn_victory_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    results: Res<GameResults>,
+    levels: Res<Levels>,
+) {
+    let continue_button = spawn_button(&mut commands, &asset_server, "Continue", Color::RED);
+    commands.entity(continue_button).insert(ContinueButton);
+
+    let font = asset_server.load("FiraSans-Bold.ttf");
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .insert(VictoryUIRoot)
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(2.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    format!("{} Cleared!", levels.current().name),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 96.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                ..default()
+            });
+
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(1.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    format!(
+                        "Stars: {}/3\nKills: {}\nMoney earned: {}",
+                        results.stars, results.kills, results.money_earned
+                    ),
+                    TextStyle {
+                        font,
+                        font_size: 36.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                ..default()
+            });
+        })
+        .with_children(|commands| spawn_tower_table(commands, &asset_server, &results.towers))
+        .add_child(continue_button);
+}
+
+fn continue_button_clicked(
+    interactions: Query<&Interaction, (With<ContinueButton>, Changed<Interaction>)>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut mouse_input: ResMut<Input<MouseButton>>,
+) {
+    for interaction in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            game_state.set(GameState::LevelSelect);
+            mouse_input.clear();
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::*;
+use bevy::math::Vec3Swizzles;
+
+/// Seconds of calm between clearing one wave and the next one starting
+const WAVE_BREAK: f32 = 3.0;
+
+/// Height above the ground at which targets walk
+const TARGET_HEIGHT: f32 = 0.2;
+
+#[derive(Reflect, Clone, Debug)]
+pub struct Wave {
+    pub count: u32,
+    /// Seconds between spawns
+    pub interval: f32,
+    pub health: i32,
+    pub speed: f32,
+}
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct Waves {
+    pub waves: Vec<Wave>,
+    /// Index of the wave being spawned, or the next one during a break
+    pub current: usize,
+    /// Targets of the current wave spawned so far
+    pub spawned: u32,
+    /// Waves that have been fully spawned and defeated
+    pub cleared: usize,
+    pub spawn_timer: Timer,
+}
+
+impl Waves {
+    pub fn new(waves: Vec<Wave>) -> Self {
+        Self {
+            waves,
+            current: 0,
+            spawned: 0,
+            cleared: 0,
+            spawn_timer: Timer::from_seconds(WAVE_BREAK, TimerMode::Once),
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.cleared >= self.waves.len()
+    }
+}
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Waves>()
+            .add_system(reset_waves.in_schedule(OnEnter(GameState::GamePlay)))
+            .add_system(
+                spawn_waves
+                    .in_set(SimulationSet::Spawn)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
+
+fn reset_waves(mut commands: Commands, levels: Res<Levels>) {
+    commands.insert_resource(Waves::new(levels.current().waves.clone()));
+}
+
+fn spawn_waves(
+    mut commands: Commands,
+    mut waves: ResMut<Waves>,
+    targets: Query<(), With<Target>>,
+    path: Res<TargetPath>,
+    assets: Res<GameAssets>,
+    time: Res<FixedTime>,
+) {
+    let Some(wave) = waves.waves.get(waves.current).cloned() else {
+        return;
+    };
+
+    if waves.spawned >= wave.count {
+        // Wait for the wave to be defeated before the break starts
+        if targets.is_empty() {
+            waves.cleared += 1;
+            waves.current += 1;
+            waves.spawned = 0;
+            waves.spawn_timer = Timer::from_seconds(WAVE_BREAK, TimerMode::Once);
+        }
+        return;
+    }
+
+    waves.spawn_timer.tick(time.period);
+    if waves.spawn_timer.finished() {
+        spawn_target(
+            &mut commands,
+            &assets,
+            path.start.extend(TARGET_HEIGHT).xzy(),
+            wave.health,
+            wave.speed,
+        );
+        waves.spawned += 1;
+        waves.spawn_timer = Timer::from_seconds(wave.interval, TimerMode::Once);
+    }
+}